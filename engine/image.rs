@@ -1,12 +1,15 @@
 use std::mem::MaybeUninit;
 use std::ptr;
 
-use anyhow::{bail, ensure, Result};
+use anyhow::{anyhow, bail, ensure, Result};
 use jpegxl_sys::JxlDecoderStatus::{
     BasicInfo, Error, FullImage, NeedImageOutBuffer, NeedMoreInput, Success,
 };
 use jpegxl_sys::*;
 
+use crate::inflate::zlib_decompress;
+use crate::utils::BinRead;
+
 pub struct Image {
     pub data: Vec<u8>,
     pub size_x: u32,
@@ -15,7 +18,15 @@ pub struct Image {
 
 impl Image {
     pub fn new(input: &[u8]) -> Result<Self> {
-        let (data, size_x, size_y) = unsafe { decode(input)? };
+        let (data, size_x, size_y) = match ImageFormat::detect(input) {
+            ImageFormat::Jxl => unsafe { decode_jxl(input)? },
+            ImageFormat::Png => {
+                let image = load_png(input)?;
+                (image.data, image.size_x, image.size_y)
+            }
+            ImageFormat::Bmp => decode_with_image_crate(input, image::ImageFormat::Bmp)?,
+            ImageFormat::Tga => decode_with_image_crate(input, image::ImageFormat::Tga)?,
+        };
 
         let inst = Self {
             data,
@@ -33,6 +44,43 @@ impl Image {
     }
 }
 
+/// Image container/codec sniffed from the leading bytes of a file, so `Image::new` can dispatch
+/// to the right backend without the caller naming a format up front.
+enum ImageFormat {
+    Jxl,
+    Png,
+    Bmp,
+    /// TGA has no magic signature, so it's the fallback when nothing else matches; data that
+    /// isn't actually TGA still gets a clear error, just raised by the `image` crate's TGA
+    /// decoder rejecting it rather than by the sniff itself.
+    Tga,
+}
+
+impl ImageFormat {
+    fn detect(input: &[u8]) -> Self {
+        const JXL_CONTAINER_SIG: [u8; 12] =
+            [0x00, 0x00, 0x00, 0x0c, b'J', b'X', b'L', b' ', 0x0d, 0x0a, 0x87, 0x0a];
+
+        match input {
+            [0xff, 0x0a, ..] => Self::Jxl,
+            _ if input.starts_with(&JXL_CONTAINER_SIG) => Self::Jxl,
+            [0x89, b'P', b'N', b'G', ..] => Self::Png,
+            [b'B', b'M', ..] => Self::Bmp,
+            _ => Self::Tga,
+        }
+    }
+}
+
+fn decode_with_image_crate(
+    input: &[u8],
+    format: image::ImageFormat,
+) -> Result<(Vec<u8>, u32, u32)> {
+    let rgba = image::load_from_memory_with_format(input, format)?.to_rgba8();
+    let (size_x, size_y) = rgba.dimensions();
+
+    Ok((rgba.into_raw(), size_x, size_y))
+}
+
 trait ConvJxlError {
     fn conv_err(self, action: &'static str) -> Result<()>;
 }
@@ -45,7 +93,7 @@ impl ConvJxlError for JxlDecoderStatus {
 }
 
 // Adapted from jpegxl-sys
-unsafe fn decode(input: &[u8]) -> Result<(Vec<u8>, u32, u32)> {
+unsafe fn decode_jxl(input: &[u8]) -> Result<(Vec<u8>, u32, u32)> {
     // Default memory manager
     let decoder = JxlDecoderCreate(ptr::null());
     ensure!(!decoder.is_null());
@@ -128,3 +176,338 @@ unsafe fn decode(input: &[u8]) -> Result<(Vec<u8>, u32, u32)> {
 
     Ok((data, size_x, size_y))
 }
+
+/// Outcome of feeding one more chunk to an `ImageDecoder`.
+pub enum DecodeState {
+    NeedMore,
+    Done(Image),
+}
+
+/// Incrementally decodes a JPEG XL stream fed in arbitrary-sized pieces (e.g. read off a socket,
+/// or from a file that's still being written), unlike `decode_jxl` which needs the whole
+/// codestream available up front. Accepts both bare codestreams and boxed containers.
+///
+/// Status: `Image::new` always has the whole buffer up front, so it calls `decode_jxl` directly;
+/// this type is public API for callers that genuinely receive a stream in pieces, none of which
+/// exist in this engine yet.
+pub struct ImageDecoder {
+    decoder: *mut JxlDecoder,
+    pixel_format: JxlPixelFormat,
+    /// Bytes handed to the decoder but not yet consumed, per `JxlDecoderReleaseInput`.
+    buffered: Vec<u8>,
+    signature_checked: bool,
+    data: Vec<u8>,
+    size_x: u32,
+    size_y: u32,
+}
+
+impl ImageDecoder {
+    pub fn new() -> Result<Self> {
+        let decoder = unsafe { JxlDecoderCreate(ptr::null()) };
+        ensure!(!decoder.is_null());
+
+        let events_wanted = BasicInfo as i32 | FullImage as i32;
+        unsafe { JxlDecoderSubscribeEvents(decoder, events_wanted) }
+            .conv_err("subscribe to events")?;
+
+        let pixel_format = JxlPixelFormat {
+            num_channels: 4,
+            data_type: JxlDataType::Uint8,
+            endianness: JxlEndianness::Native,
+            align: 0,
+        };
+
+        Ok(Self {
+            decoder,
+            pixel_format,
+            buffered: vec![],
+            signature_checked: false,
+            data: vec![],
+            size_x: 0,
+            size_y: 0,
+        })
+    }
+
+    /// Feeds another chunk of the stream. Call repeatedly with successive chunks until this
+    /// returns `DecodeState::Done`.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<DecodeState> {
+        self.buffered.extend_from_slice(chunk);
+
+        if !self.signature_checked {
+            if self.buffered.len() < 2 {
+                return Ok(DecodeState::NeedMore);
+            }
+
+            let signature =
+                unsafe { JxlSignatureCheck(self.buffered.as_ptr(), self.buffered.len()) };
+
+            match signature {
+                JxlSignature::NotEnoughBytes => return Ok(DecodeState::NeedMore),
+                JxlSignature::Codestream | JxlSignature::Container => {}
+                _ => bail!("not a JPEG XL codestream or container"),
+            }
+
+            self.signature_checked = true;
+        }
+
+        unsafe {
+            JxlDecoderSetInput(self.decoder, self.buffered.as_ptr(), self.buffered.len())
+                .conv_err("set input")?;
+
+            loop {
+                let status = JxlDecoderProcessInput(self.decoder);
+
+                match status {
+                    Error => bail!("decoder error"),
+
+                    NeedMoreInput => {
+                        let unconsumed = JxlDecoderReleaseInput(self.decoder);
+                        let consumed = self.buffered.len() - unconsumed;
+
+                        self.buffered.drain(..consumed);
+
+                        return Ok(DecodeState::NeedMore);
+                    }
+
+                    BasicInfo => {
+                        let basic_info = {
+                            let mut info = MaybeUninit::uninit();
+                            JxlDecoderGetBasicInfo(self.decoder, info.as_mut_ptr())
+                                .conv_err("get basic info")?;
+                            info.assume_init()
+                        };
+
+                        self.size_x = basic_info.xsize;
+                        self.size_y = basic_info.ysize;
+                    }
+
+                    NeedImageOutBuffer => {
+                        ensure!(self.data.is_empty(), "out buffer already allocated");
+
+                        let mut size = 0;
+                        JxlDecoderImageOutBufferSize(self.decoder, &self.pixel_format, &mut size)
+                            .conv_err("get buffer size")?;
+
+                        self.data.resize(size, 0);
+
+                        JxlDecoderSetImageOutBuffer(
+                            self.decoder,
+                            &self.pixel_format,
+                            self.data.as_mut_ptr().cast(),
+                            size,
+                        )
+                        .conv_err("set output buffer")?;
+                    }
+
+                    FullImage => continue,
+
+                    Success => {
+                        let size_decoded = self.data.len();
+                        let size_expected =
+                            (self.size_x * self.size_y * self.pixel_format.num_channels) as usize;
+
+                        ensure!(
+                            size_decoded == size_expected,
+                            "unexpected image size: {} != {}",
+                            size_decoded,
+                            size_expected
+                        );
+
+                        let image = Image {
+                            data: std::mem::take(&mut self.data),
+                            size_x: self.size_x,
+                            size_y: self.size_y,
+                        };
+
+                        return Ok(DecodeState::Done(image));
+                    }
+
+                    _ => bail!("unexpected decoder status: {:#?}", status),
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ImageDecoder {
+    fn drop(&mut self) {
+        unsafe { JxlDecoderDestroy(self.decoder) };
+    }
+}
+
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+/// An `IHDR` chunk's fields, everything needed to reconstruct `IDAT`'s scanlines.
+struct PngHeader {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+    interlace: u8,
+}
+
+impl PngHeader {
+    fn parse(data: &[u8]) -> Result<Self> {
+        ensure!(data.len() == 13, "malformed IHDR chunk: expected 13 bytes, got {}", data.len());
+
+        let compression = data[10];
+        let filter = data[11];
+
+        ensure!(compression == 0, "unsupported PNG compression method: {compression}");
+        ensure!(filter == 0, "unsupported PNG filter method: {filter}");
+
+        Ok(Self {
+            width: data.c_u32_be(0)?,
+            height: data.c_u32_be(4)?,
+            bit_depth: data[8],
+            color_type: data[9],
+            interlace: data[12],
+        })
+    }
+}
+
+/// A hand-rolled PNG decoder (signature + chunk structure + zlib-inflated scanlines), used by
+/// `Image::new` for `ImageFormat::Png` instead of pulling in `image`'s own PNG backend (still used
+/// for `Bmp`/`Tga` via `decode_with_image_crate`). Supports only 8-bit, non-interlaced RGB and
+/// RGBA color types; anything else (palette, grayscale, 16-bit, Adam7 interlacing) is rejected
+/// with a descriptive error rather than guessed at.
+pub fn load_png(bytes: &[u8]) -> Result<Image> {
+    ensure!(bytes.starts_with(&PNG_SIGNATURE), "not a PNG file: bad signature");
+
+    let mut header = None;
+    let mut idat = Vec::new();
+    let mut pos = PNG_SIGNATURE.len();
+
+    loop {
+        let length = bytes.c_u32_be(pos)? as usize;
+        let tag = bytes.c_ident(pos + 4)?;
+        let data = bytes.c_data(pos + 8..pos + 8 + length)?;
+        let crc = bytes.c_u32_be(pos + 8 + length)?;
+
+        let crc_span = bytes.c_data(pos + 4..pos + 8 + length)?;
+        ensure!(crc32(crc_span) == crc, "PNG chunk {:?} failed CRC check", tag);
+
+        match &tag {
+            b"IHDR" => header = Some(PngHeader::parse(data)?),
+            b"IDAT" => idat.extend_from_slice(data),
+            b"IEND" => break,
+            _ => {}
+        }
+
+        pos += 12 + length;
+    }
+
+    let header = header.ok_or_else(|| anyhow!("PNG file has no IHDR chunk"))?;
+
+    ensure!(header.bit_depth == 8, "unsupported PNG bit depth: {}", header.bit_depth);
+    ensure!(header.interlace == 0, "interlaced PNGs are not supported");
+
+    let channels = match header.color_type {
+        2 => 3,
+        6 => 4,
+        other => bail!("unsupported PNG color type: {other}"),
+    };
+
+    let raw = zlib_decompress(&idat)?;
+    let data =
+        reconstruct_scanlines(&raw, header.width as usize, header.height as usize, channels)?;
+
+    Ok(Image {
+        data,
+        size_x: header.width,
+        size_y: header.height,
+    })
+}
+
+/// Undoes PNG's per-row filtering (`raw` is the zlib-inflated `IDAT` payload, one filter-type byte
+/// followed by `width * channels` bytes per row) and expands RGB rows to RGBA.
+fn reconstruct_scanlines(
+    raw: &[u8],
+    width: usize,
+    height: usize,
+    channels: usize,
+) -> Result<Vec<u8>> {
+    let stride = width * channels;
+    let row_len = stride + 1;
+
+    ensure!(
+        raw.len() == row_len * height,
+        "decompressed PNG data has unexpected length: {} != {}",
+        raw.len(),
+        row_len * height
+    );
+
+    let mut prev_row = vec![0u8; stride];
+    let mut out = Vec::with_capacity(width * height * 4);
+
+    for y in 0..height {
+        let row = &raw[y * row_len..(y + 1) * row_len];
+        let filter = row[0];
+        let filtered = &row[1..];
+
+        let mut cur_row = vec![0u8; stride];
+
+        for x in 0..stride {
+            let a = if x >= channels { cur_row[x - channels] } else { 0 };
+            let b = prev_row[x];
+            let c = if x >= channels { prev_row[x - channels] } else { 0 };
+
+            cur_row[x] = match filter {
+                0 => filtered[x],
+                1 => filtered[x].wrapping_add(a),
+                2 => filtered[x].wrapping_add(b),
+                3 => filtered[x].wrapping_add(average(a, b)),
+                4 => filtered[x].wrapping_add(paeth(a, b, c)),
+                other => bail!("unsupported PNG filter type: {other}"),
+            };
+        }
+
+        for pixel in cur_row.chunks_exact(channels) {
+            out.extend_from_slice(&pixel[..3]);
+            out.push(if channels == 4 { pixel[3] } else { 255 });
+        }
+
+        prev_row = cur_row;
+    }
+
+    Ok(out)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn average(a: u8, b: u8) -> u8 {
+    ((u16::from(a) + u16::from(b)) / 2) as u8
+}
+
+/// The Paeth predictor: picks whichever of the left (`a`), up (`b`), or up-left (`c`) reconstructed
+/// byte comes closest to `a + b - c`, breaking ties toward `a` then `b`.
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = i32::from(a) + i32::from(b) - i32::from(c);
+    let pa = (p - i32::from(a)).abs();
+    let pb = (p - i32::from(b)).abs();
+    let pc = (p - i32::from(c)).abs();
+
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// CRC-32/ISO-HDLC (the variant PNG uses), computed bit-by-bit rather than via a lookup table
+/// since chunk verification isn't a hot path.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+
+    for &byte in data {
+        crc ^= u32::from(byte);
+
+        for _ in 0..8 {
+            let mask = 0u32.wrapping_sub(crc & 1);
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    !crc
+}
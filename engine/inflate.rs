@@ -0,0 +1,327 @@
+//! A pure-Rust, dependency-free zlib/DEFLATE decompressor (RFC 1950/1951), so formats like PNG
+//! that embed zlib streams can be parsed without pulling in an external decompression crate.
+//! Ports the classic "simple" canonical-Huffman decode from RFC 1951's reference implementation,
+//! not a performance-tuned table-driven one, since the PNGs this engine loads are asset-sized.
+
+use anyhow::{bail, ensure, Result};
+
+const MAX_BITS: usize = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order code-length codes are transmitted in for a dynamic block's code-length table.
+const CODE_LENGTH_ORDER: [usize; 19] =
+    [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+struct BitReader<'d> {
+    data: &'d [u8],
+    byte_pos: usize,
+    bit_pos: u32,
+}
+
+impl<'d> BitReader<'d> {
+    fn new(data: &'d [u8]) -> Self {
+        Self { data, byte_pos: 0, bit_pos: 0 }
+    }
+
+    fn bit(&mut self) -> Result<u32> {
+        let byte = *self.data.get(self.byte_pos).ok_or_else(|| {
+            anyhow::anyhow!("truncated DEFLATE stream at byte {}", self.byte_pos)
+        })?;
+        let bit = u32::from((byte >> self.bit_pos) & 1);
+
+        self.bit_pos += 1;
+        if self.bit_pos == 8 {
+            self.bit_pos = 0;
+            self.byte_pos += 1;
+        }
+
+        Ok(bit)
+    }
+
+    fn bits(&mut self, count: u32) -> Result<u32> {
+        let mut value = 0;
+
+        for i in 0..count {
+            value |= self.bit()? << i;
+        }
+
+        Ok(value)
+    }
+
+    /// Discards any partial byte, for a stored block's byte-aligned length/data.
+    fn align_to_byte(&mut self) {
+        if self.bit_pos != 0 {
+            self.bit_pos = 0;
+            self.byte_pos += 1;
+        }
+    }
+
+    fn take_bytes(&mut self, count: usize) -> Result<&'d [u8]> {
+        let bytes = self
+            .data
+            .get(self.byte_pos..self.byte_pos + count)
+            .ok_or_else(|| anyhow::anyhow!("truncated stored block at byte {}", self.byte_pos))?;
+
+        self.byte_pos += count;
+
+        Ok(bytes)
+    }
+}
+
+/// A canonical Huffman code table built from per-symbol code lengths, decoded via RFC 1951's
+/// reference "simple" algorithm (bit-by-bit, comparing against the first code of each length)
+/// rather than a precomputed lookup table.
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn build(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for len in 1..=MAX_BITS {
+            offsets[len] = offsets[len - 1] + counts[len - 1];
+        }
+
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                #[allow(clippy::cast_possible_truncation)]
+                let symbol = symbol as u16;
+
+                symbols[offsets[len as usize] as usize] = symbol;
+                offsets[len as usize] += 1;
+            }
+        }
+
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> Result<u16> {
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+
+        for len in 1..=MAX_BITS {
+            #[allow(clippy::cast_possible_wrap)]
+            let bit = bits.bit()? as i32;
+
+            code |= bit;
+
+            let count = i32::from(self.counts[len]);
+
+            if code - first < count {
+                #[allow(clippy::cast_sign_loss)]
+                let symbol_index = (index + (code - first)) as usize;
+
+                return Ok(self.symbols[symbol_index]);
+            }
+
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        bail!("invalid Huffman code")
+    }
+}
+
+fn fixed_huffman_trees() -> (Huffman, Huffman) {
+    let mut lit_lengths = [0u8; 288];
+    lit_lengths[0..144].fill(8);
+    lit_lengths[144..256].fill(9);
+    lit_lengths[256..280].fill(7);
+    lit_lengths[280..288].fill(8);
+
+    let dist_lengths = [5u8; 30];
+
+    (Huffman::build(&lit_lengths), Huffman::build(&dist_lengths))
+}
+
+fn dynamic_huffman_trees(bits: &mut BitReader) -> Result<(Huffman, Huffman)> {
+    let hlit = bits.bits(5)? as usize + 257;
+    let hdist = bits.bits(5)? as usize + 1;
+    let hclen = bits.bits(4)? as usize + 4;
+
+    let mut cl_lengths = [0u8; 19];
+    for &order in CODE_LENGTH_ORDER.iter().take(hclen) {
+        #[allow(clippy::cast_possible_truncation)]
+        let len = bits.bits(3)? as u8;
+
+        cl_lengths[order] = len;
+    }
+
+    let cl_tree = Huffman::build(&cl_lengths);
+
+    let mut lengths = Vec::with_capacity(hlit + hdist);
+    while lengths.len() < hlit + hdist {
+        let symbol = cl_tree.decode(bits)?;
+
+        match symbol {
+            0..=15 => {
+                #[allow(clippy::cast_possible_truncation)]
+                lengths.push(symbol as u8);
+            }
+            16 => {
+                let prev = *lengths.last().ok_or_else(|| {
+                    anyhow::anyhow!("repeat code 16 with no previous length")
+                })?;
+                let repeat = bits.bits(2)? + 3;
+
+                lengths.extend(std::iter::repeat(prev).take(repeat as usize));
+            }
+            17 => {
+                let repeat = bits.bits(3)? + 3;
+
+                lengths.extend(std::iter::repeat(0).take(repeat as usize));
+            }
+            18 => {
+                let repeat = bits.bits(7)? + 11;
+
+                lengths.extend(std::iter::repeat(0).take(repeat as usize));
+            }
+            _ => bail!("invalid code length symbol: {symbol}"),
+        }
+    }
+
+    ensure!(lengths.len() == hlit + hdist, "code length table overrun");
+
+    let lit_tree = Huffman::build(&lengths[..hlit]);
+    let dist_tree = Huffman::build(&lengths[hlit..]);
+
+    Ok((lit_tree, dist_tree))
+}
+
+fn inflate_block(
+    bits: &mut BitReader,
+    lit_tree: &Huffman,
+    dist_tree: &Huffman,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    loop {
+        let symbol = lit_tree.decode(bits)?;
+
+        match symbol {
+            0..=255 => {
+                #[allow(clippy::cast_possible_truncation)]
+                out.push(symbol as u8);
+            }
+            256 => return Ok(()),
+            257..=285 => {
+                let index = symbol as usize - 257;
+                let extra = bits.bits(u32::from(LENGTH_EXTRA_BITS[index]))?;
+                let length = u32::from(LENGTH_BASE[index]) + extra;
+
+                let dist_symbol = dist_tree.decode(bits)? as usize;
+                ensure!(dist_symbol < DIST_BASE.len(), "invalid distance symbol");
+
+                let dist_extra = bits.bits(u32::from(DIST_EXTRA_BITS[dist_symbol]))?;
+                let distance = u32::from(DIST_BASE[dist_symbol]) + dist_extra;
+
+                ensure!(
+                    (distance as usize) <= out.len(),
+                    "back-reference distance {distance} exceeds output so far ({})",
+                    out.len()
+                );
+
+                let start = out.len() - distance as usize;
+                for i in 0..length as usize {
+                    out.push(out[start + i]);
+                }
+            }
+            _ => bail!("invalid literal/length symbol: {symbol}"),
+        }
+    }
+}
+
+/// Inflates a raw DEFLATE stream (no zlib wrapper).
+fn inflate(data: &[u8]) -> Result<Vec<u8>> {
+    let mut bits = BitReader::new(data);
+    let mut out = Vec::new();
+
+    loop {
+        let is_final = bits.bit()? == 1;
+        let block_type = bits.bits(2)?;
+
+        match block_type {
+            0 => {
+                bits.align_to_byte();
+
+                let len_bytes = bits.take_bytes(4)?;
+                let len = u16::from_le_bytes([len_bytes[0], len_bytes[1]]);
+
+                out.extend_from_slice(bits.take_bytes(len as usize)?);
+            }
+            1 => {
+                let (lit_tree, dist_tree) = fixed_huffman_trees();
+                inflate_block(&mut bits, &lit_tree, &dist_tree, &mut out)?;
+            }
+            2 => {
+                let (lit_tree, dist_tree) = dynamic_huffman_trees(&mut bits)?;
+                inflate_block(&mut bits, &lit_tree, &dist_tree, &mut out)?;
+            }
+            _ => bail!("reserved DEFLATE block type"),
+        }
+
+        if is_final {
+            return Ok(out);
+        }
+    }
+}
+
+/// Inflates a zlib stream (RFC 1950): a 2-byte header, a DEFLATE stream, then an Adler-32 trailer
+/// which is verified against the decompressed output.
+pub fn zlib_decompress(data: &[u8]) -> Result<Vec<u8>> {
+    ensure!(data.len() >= 6, "zlib stream too short");
+
+    let cmf = data[0];
+    let flg = data[1];
+
+    ensure!(cmf & 0x0f == 8, "unsupported zlib compression method: {}", cmf & 0x0f);
+    ensure!((u16::from(cmf) * 256 + u16::from(flg)) % 31 == 0, "invalid zlib header checksum");
+    ensure!(flg & 0x20 == 0, "zlib preset dictionaries are not supported");
+
+    let out = inflate(&data[2..data.len() - 4])?;
+
+    let expected = u32::from_be_bytes(
+        data[data.len() - 4..].try_into().unwrap_or_else(|_| unreachable!()),
+    );
+    let actual = adler32(&out);
+    ensure!(actual == expected, "zlib Adler-32 mismatch: expected {expected:#x}, got {actual:#x}");
+
+    Ok(out)
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD_ADLER: u32 = 65521;
+
+    let mut a = 1u32;
+    let mut b = 0u32;
+
+    for &byte in data {
+        a = (a + u32::from(byte)) % MOD_ADLER;
+        b = (b + a) % MOD_ADLER;
+    }
+
+    (b << 16) | a
+}
@@ -1,6 +1,124 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
 use crate::utils::pair_to_i32;
 use crate::window::Key;
 
+/// A movement action an input source (currently only keyboard keys) can be bound to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    Forward,
+    Back,
+    Left,
+    Right,
+    Up,
+}
+
+/// Maps physical keys to `Action`s, so `InputHandler` doesn't hardcode WASD + Space.
+pub struct KeyBindings {
+    bindings: HashMap<Key, Action>,
+}
+
+impl KeyBindings {
+    pub fn defaults() -> Self {
+        let bindings = HashMap::from([
+            (Key::W, Action::Forward),
+            (Key::S, Action::Back),
+            (Key::A, Action::Left),
+            (Key::D, Action::Right),
+            (Key::Space, Action::Up),
+        ]);
+
+        Self { bindings }
+    }
+
+    /// Loads bindings from a TOML table mapping action names to key names, e.g.:
+    /// `forward = "W"`, `up = "Space"`. Actions not mentioned keep their `defaults()` key, unless
+    /// that key is rebound to a mentioned action, in which case it moves to that action.
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        let raw: HashMap<String, String> = toml::from_str(contents)?;
+        let mut bindings = Self::defaults().bindings;
+
+        for (action_name, key_name) in raw {
+            let action =
+                parse_action(&action_name).ok_or_else(|| anyhow!("unknown action: {action_name}"))?;
+            let key = parse_key(&key_name).ok_or_else(|| anyhow!("unknown key: {key_name}"))?;
+
+            bindings.retain(|_, bound_action| *bound_action != action);
+            bindings.insert(key, action);
+        }
+
+        Ok(Self { bindings })
+    }
+
+    fn action_for(&self, key: Key) -> Option<Action> {
+        self.bindings.get(&key).copied()
+    }
+}
+
+fn parse_action(name: &str) -> Option<Action> {
+    match name.to_ascii_lowercase().as_str() {
+        "forward" => Some(Action::Forward),
+        "back" => Some(Action::Back),
+        "left" => Some(Action::Left),
+        "right" => Some(Action::Right),
+        "up" => Some(Action::Up),
+        _ => None,
+    }
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    match name.to_ascii_uppercase().as_str() {
+        "A" => Some(Key::A),
+        "B" => Some(Key::B),
+        "C" => Some(Key::C),
+        "D" => Some(Key::D),
+        "E" => Some(Key::E),
+        "F" => Some(Key::F),
+        "G" => Some(Key::G),
+        "H" => Some(Key::H),
+        "I" => Some(Key::I),
+        "J" => Some(Key::J),
+        "K" => Some(Key::K),
+        "L" => Some(Key::L),
+        "M" => Some(Key::M),
+        "N" => Some(Key::N),
+        "O" => Some(Key::O),
+        "P" => Some(Key::P),
+        "Q" => Some(Key::Q),
+        "R" => Some(Key::R),
+        "S" => Some(Key::S),
+        "T" => Some(Key::T),
+        "U" => Some(Key::U),
+        "V" => Some(Key::V),
+        "W" => Some(Key::W),
+        "X" => Some(Key::X),
+        "Y" => Some(Key::Y),
+        "Z" => Some(Key::Z),
+        "SPACE" => Some(Key::Space),
+        "UP" => Some(Key::Up),
+        "DOWN" => Some(Key::Down),
+        "LEFT" => Some(Key::Left),
+        "RIGHT" => Some(Key::Right),
+        _ => None,
+    }
+}
+
+/// A gamepad stick axis fed through `InputHandler::handle_axis`. `LookX`/`LookY` feed into the
+/// same `mouse_diff_x`/`mouse_diff_y` the mouse path writes, so the camera only has one input
+/// surface to read regardless of whether the user is on mouse+keyboard or a controller.
+pub enum Axis {
+    Forward,
+    Right,
+    LookX,
+    LookY,
+}
+
+/// Scales a `[-1.0, 1.0]` look-stick deflection into the same units as one frame's mouse-motion
+/// delta; tune to taste.
+const LOOK_AXIS_SENSITIVITY: f32 = 10.0;
+
 pub struct InputHandler {
     mouse_prev_x: i32,
     mouse_prev_y: i32,
@@ -8,18 +126,24 @@ pub struct InputHandler {
     mouse_diff_x: i32,
     mouse_diff_y: i32,
 
-    forward: i8,
-    right: i8,
+    forward_axis: f32,
+    right_axis: f32,
 
     key_forward: bool,
     key_right: bool,
     key_back: bool,
     key_left: bool,
     key_up: bool,
+
+    bindings: KeyBindings,
 }
 
 impl InputHandler {
-    pub const fn new(mouse_prev: (f64, f64)) -> Self {
+    pub fn new(mouse_prev: (f64, f64)) -> Self {
+        Self::with_bindings(mouse_prev, KeyBindings::defaults())
+    }
+
+    pub fn with_bindings(mouse_prev: (f64, f64), bindings: KeyBindings) -> Self {
         let (mouse_prev_x, mouse_prev_y) = pair_to_i32(mouse_prev);
 
         Self {
@@ -27,13 +151,14 @@ impl InputHandler {
             mouse_prev_y,
             mouse_diff_x: 0,
             mouse_diff_y: 0,
-            forward: 0,
-            right: 0,
+            forward_axis: 0.0,
+            right_axis: 0.0,
             key_forward: false,
             key_right: false,
             key_back: false,
             key_left: false,
             key_up: false,
+            bindings,
         }
     }
 
@@ -47,58 +172,90 @@ impl InputHandler {
         self.mouse_prev_y = y;
     }
 
+    /// Feeds a gamepad stick axis in `[-1.0, 1.0]`. `Forward`/`Right` overwrite the
+    /// keyboard-driven axis state directly (bypassing the digital press/release bookkeeping, so
+    /// simultaneous keyboard input for the same axis will be overridden on the next poll).
+    pub fn handle_axis(&mut self, axis: Axis, value: f32) {
+        let value = value.clamp(-1.0, 1.0);
+
+        match axis {
+            Axis::Forward => self.forward_axis = value,
+            Axis::Right => self.right_axis = value,
+            #[allow(clippy::cast_possible_truncation)]
+            Axis::LookX => self.mouse_diff_x = (value * LOOK_AXIS_SENSITIVITY) as i32,
+            #[allow(clippy::cast_possible_truncation)]
+            Axis::LookY => self.mouse_diff_y = (value * LOOK_AXIS_SENSITIVITY) as i32,
+        }
+    }
+
     pub fn handle_key_press(&mut self, key: Key) {
-        match key {
-            Key::W => {
+        let Some(action) = self.bindings.action_for(key) else {
+            return;
+        };
+
+        match action {
+            Action::Forward => {
                 self.key_forward = true;
-                self.forward = 1;
+                self.forward_axis = 1.0;
             }
-            Key::S => {
+            Action::Back => {
                 self.key_back = true;
-                self.forward = -1;
+                self.forward_axis = -1.0;
             }
-            Key::D => {
+            Action::Right => {
                 self.key_right = true;
-                self.right = 1;
+                self.right_axis = 1.0;
             }
-            Key::A => {
+            Action::Left => {
                 self.key_left = true;
-                self.right = -1;
+                self.right_axis = -1.0;
             }
-            Key::Space => self.key_up = true,
-            _ => (),
+            Action::Up => self.key_up = true,
         }
     }
 
     pub fn handle_key_release(&mut self, key: Key) {
-        match key {
-            Key::W => {
+        let Some(action) = self.bindings.action_for(key) else {
+            return;
+        };
+
+        match action {
+            Action::Forward => {
                 self.key_forward = false;
-                self.forward = -i8::from(self.key_back);
+                self.forward_axis = if self.key_back { -1.0 } else { 0.0 };
             }
-            Key::S => {
+            Action::Back => {
                 self.key_back = false;
-                self.forward = i8::from(self.key_forward);
+                self.forward_axis = if self.key_forward { 1.0 } else { 0.0 };
             }
-            Key::D => {
+            Action::Right => {
                 self.key_right = false;
-                self.right = -i8::from(self.key_left);
+                self.right_axis = if self.key_left { -1.0 } else { 0.0 };
             }
-            Key::A => {
+            Action::Left => {
                 self.key_left = false;
-                self.right = i8::from(self.key_right);
+                self.right_axis = if self.key_right { 1.0 } else { 0.0 };
             }
-            Key::Space => self.key_up = false,
-            _ => (),
+            Action::Up => self.key_up = false,
         }
     }
 
+    /// Clamped, rounded view of `forward_f32` for callers still expecting a digital `-1/0/1`.
     pub fn forward(&self) -> i8 {
-        self.forward
+        round_axis(self.forward_axis)
     }
 
+    /// Clamped, rounded view of `right_f32` for callers still expecting a digital `-1/0/1`.
     pub fn right(&self) -> i8 {
-        self.right
+        round_axis(self.right_axis)
+    }
+
+    pub fn forward_f32(&self) -> f32 {
+        self.forward_axis
+    }
+
+    pub fn right_f32(&self) -> f32 {
+        self.right_axis
     }
 
     pub fn mouse_diff_x(&self) -> i32 {
@@ -109,3 +266,8 @@ impl InputHandler {
         self.mouse_diff_y
     }
 }
+
+#[allow(clippy::cast_possible_truncation)]
+fn round_axis(axis: f32) -> i8 {
+    axis.clamp(-1.0, 1.0).round() as i8
+}
@@ -23,10 +23,12 @@ pub mod world;
 
 mod camera;
 mod image;
+mod inflate;
 mod input;
 mod rand;
 mod renderer;
 mod utils;
+mod y4m;
 
 #[global_allocator]
 static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
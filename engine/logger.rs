@@ -1,5 +1,8 @@
 use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use log::{Level, LevelFilter, Log, Metadata, Record};
@@ -9,6 +12,49 @@ static VERBOSE: AtomicBool = AtomicBool::new(false);
 pub struct Logger {
     level: LevelFilter,
     colors: bool,
+    file: Option<FileSink>,
+}
+
+/// Writes log lines (always uncolored) to `path.YYYY-MM-DD.log`, reopening the file whenever
+/// `Timestamp`'s date rolls over so a long-running session doesn't pile everything into one file.
+struct FileSink {
+    path: &'static str,
+    state: Mutex<Option<(BufWriter<std::fs::File>, (u16, u16, u16))>>,
+}
+
+impl FileSink {
+    fn new(path: &'static str) -> Self {
+        Self {
+            path,
+            state: Mutex::new(None),
+        }
+    }
+
+    fn write_line(&self, timestamp: &Timestamp, line: &str) {
+        let today = (timestamp.year, timestamp.month, timestamp.day);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let needs_reopen = !matches!(&*state, Some((_, day)) if *day == today);
+
+        if needs_reopen {
+            let file_path =
+                format!("{}.{:04}-{:02}-{:02}.log", self.path, today.0, today.1, today.2);
+
+            match OpenOptions::new().create(true).append(true).open(&file_path) {
+                Ok(file) => *state = Some((BufWriter::new(file), today)),
+                Err(e) => {
+                    eprintln!("failed to open log file {}: {}", file_path, e);
+                    return;
+                }
+            }
+        }
+
+        if let Some((writer, _)) = state.as_mut() {
+            if let Err(e) = writeln!(writer, "{}", line).and_then(|()| writer.flush()) {
+                eprintln!("failed to write to log file: {}", e);
+            }
+        }
+    }
 }
 
 struct Timestamp {
@@ -25,6 +71,7 @@ impl Logger {
         Self {
             level,
             colors: true,
+            file: None,
         }
     }
 
@@ -33,6 +80,13 @@ impl Logger {
         self
     }
 
+    /// Adds a rotating file sink alongside stdout, writing to `path.YYYY-MM-DD.log` with colors
+    /// always stripped regardless of `with_colors`.
+    pub fn with_file(&mut self, path: &'static str) -> &mut Self {
+        self.file = Some(FileSink::new(path));
+        self
+    }
+
     pub fn init(self) {
         log::set_max_level(self.level);
         log::set_boxed_logger(Box::new(self)).expect("logger already set");
@@ -49,9 +103,9 @@ impl Log for Logger {
             return;
         }
 
-        let level = {
-            let level = record.level().to_string();
+        let level_plain = format!("{:<5}", record.level());
 
+        let level = {
             let red = "\x1b[31m";
             let yellow = "\x1b[33m";
             let cyan = "\x1b[36m";
@@ -67,9 +121,9 @@ impl Log for Logger {
                     Level::Trace => normal,
                 };
 
-                format!("{}{:<5}{}", color, level, normal)
+                format!("{}{}{}", color, level_plain, normal)
             } else {
-                format!("{:<5}", level)
+                level_plain.clone()
             }
         };
 
@@ -93,6 +147,13 @@ impl Log for Logger {
         let timestamp = Timestamp::new();
 
         println!("{} {} [{}{}] {}", timestamp, level, location, thread, record.args());
+
+        if let Some(file) = &self.file {
+            let line =
+                format!("{} {} [{}{}] {}", timestamp, level_plain, location, thread, record.args());
+
+            file.write_line(&timestamp, &line);
+        }
     }
 
     fn flush(&self) {}
@@ -1,5 +1,8 @@
+use std::path::Path;
+
 use anyhow::Result;
 use glam::vec3;
+use log::warn;
 
 use crate::camera::Camera;
 use crate::input::InputHandler;
@@ -7,6 +10,7 @@ use crate::renderer::Renderer;
 use crate::utils::to_f32;
 use crate::window::{Event, Key, Resolution, Window};
 use crate::world::World;
+use crate::y4m::Y4mWriter;
 
 pub const UPDATES_PER_SECOND: i16 = 60;
 pub const DT: f32 = 1.0 / (UPDATES_PER_SECOND as f32);
@@ -75,7 +79,19 @@ impl MainLoop {
         }
     }
 
-    pub fn benchmark(&mut self, frames: usize) {
+    pub fn benchmark(&mut self, frames: usize, record_path: Option<&Path>) -> Result<()> {
+        let mut recorder = record_path
+            .map(|path| {
+                let fps_num = u32::try_from(UPDATES_PER_SECOND).unwrap_or_else(|_| unreachable!());
+
+                Y4mWriter::new(path, self.window.width(), self.window.height(), fps_num, 1)
+            })
+            .transpose()?;
+
+        if recorder.is_some() {
+            self.renderer.enable_frame_capture();
+        }
+
         let mut current_time = self.window.current_time();
         let mut frame = 0;
 
@@ -90,12 +106,21 @@ impl MainLoop {
 
             self.draw();
 
+            if let Some(recorder) = &mut recorder {
+                match self.renderer.read_back_frame() {
+                    Some(rgb) => recorder.write_frame(&rgb)?,
+                    None => warn!("--record: renderer has no frame to read back, skipping"),
+                }
+            }
+
             frame += 1;
 
             if frame >= frames {
                 break;
             }
         }
+
+        Ok(())
     }
 
     fn handle_event(
@@ -24,11 +24,57 @@ impl Wyhash64 {
         (((y >> 64) ^ y) & 0xffff_ffff_ffff_ffff) as u64
     }
 
+    /// Samples uniformly from `range` using Lemire's nearly-divisionless method, unbiased
+    /// unlike a naive `gen() % span`. Panics on an empty or inverted range, same as indexing
+    /// it would.
     pub fn gen_in_range(&mut self, range: Range<u64>) -> u64 {
+        assert!(range.start < range.end, "empty or inverted range: {}..{}", range.start, range.end);
+
         let min = range.start;
-        let max = range.end;
+        let span = range.end - range.start;
+
+        let mut m = u128::from(self.gen()) * u128::from(span);
+        #[allow(clippy::cast_possible_truncation)]
+        let mut l = m as u64;
+
+        if l < span {
+            let threshold = span.wrapping_neg() % span;
+
+            while l < threshold {
+                m = u128::from(self.gen()) * u128::from(span);
+                #[allow(clippy::cast_possible_truncation)]
+                {
+                    l = m as u64;
+                }
+            }
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        let high = (m >> 64) as u64;
+
+        min + high
+    }
+
+    /// Returns a uniform random value in `[0, 1)`, built from the top 24 bits of `gen()`: one of
+    /// 2^24 evenly spaced values (multiples of 2^-24), each equally likely.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn gen_f32(&mut self) -> f32 {
+        let top_bits = self.gen() >> (64 - 24);
+
+        top_bits as f32 * 2f32.powi(-24)
+    }
+
+    /// Returns a uniform random value in `[0, 1)`, built from the top 53 bits of `gen()`: one of
+    /// 2^53 evenly spaced values (multiples of 2^-53), each equally likely.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn gen_f64(&mut self) -> f64 {
+        let top_bits = self.gen() >> (64 - 53);
+
+        top_bits as f64 * 2f64.powi(-53)
+    }
 
-        min + self.gen() % (max - min)
+    pub fn gen_in_range_f32(&mut self, range: Range<f32>) -> f32 {
+        range.start + self.gen_f32() * (range.end - range.start)
     }
 }
 
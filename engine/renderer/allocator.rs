@@ -0,0 +1,269 @@
+use ash::vk;
+
+use super::vulkan::find_memory_type;
+use crate::utils::*;
+
+/// Size of each `vk::DeviceMemory` block an `Allocator` requests from the driver. Chosen well
+/// above `maxMemoryAllocationCount`'s typical floor (4096) so a scene with many meshes and
+/// textures doesn't burn through the driver's allocation budget one resource at a time.
+const BLOCK_SIZE: u64 = 256 * 1024 * 1024;
+
+/// A sub-allocation handed out by an `Allocator`, suitable for `bind_buffer_memory` /
+/// `bind_image_memory` as `(memory(), offset())`.
+#[derive(Clone, Copy)]
+pub struct Allocation {
+    memory: vk::DeviceMemory,
+    offset: u64,
+    size: u64,
+    block_mapped_ptr: Option<*mut u8>,
+}
+
+impl Allocation {
+    pub fn memory(&self) -> vk::DeviceMemory {
+        self.memory
+    }
+
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Host pointer at this allocation's offset, if its block is host-visible and therefore
+    /// persistently mapped. Lets callers `memcpy` straight in without `map_memory`/`unmap_memory`
+    /// per upload.
+    pub fn mapped_ptr(&self) -> Option<*mut u8> {
+        self.block_mapped_ptr.map(|p| unsafe { p.add(offset_usize(self.offset)) })
+    }
+}
+
+struct Block {
+    memory: vk::DeviceMemory,
+    memory_type_index: u32,
+    mapped_ptr: Option<*mut u8>,
+    // Free byte ranges as (offset, size), kept sorted by offset and coalesced on free.
+    free_ranges: Vec<(u64, u64)>,
+}
+
+/// Owns a handful of large `vk::DeviceMemory` blocks, at least one per memory-type-index in use
+/// (another is allocated whenever no existing block of that type has room), and hands
+/// out `(block, offset)` sub-allocations from them instead of calling `allocate_memory` per
+/// resource. Keeps host-visible blocks mapped for the lifetime of the block.
+pub struct Allocator {
+    device: ash::Device,
+    mem_properties: vk::PhysicalDeviceMemoryProperties,
+    blocks: Vec<Block>,
+}
+
+impl Allocator {
+    pub fn new(device: &ash::Device, mem_properties: &vk::PhysicalDeviceMemoryProperties) -> Self {
+        Self {
+            device: device.clone(),
+            mem_properties: *mem_properties,
+            blocks: vec![],
+        }
+    }
+
+    pub fn alloc(
+        &mut self,
+        req: vk::MemoryRequirements,
+        properties: vk::MemoryPropertyFlags,
+    ) -> Allocation {
+        let memory_type_index = find_memory_type(req.memory_type_bits, properties, &self.mem_properties)
+            .check_err("find appropriate memory type");
+
+        if let Some(allocation) = self.alloc_from_existing_block(memory_type_index, req) {
+            return allocation;
+        }
+
+        self.alloc_new_block(memory_type_index, req, properties)
+    }
+
+    /// Releases a sub-allocation back to its block's free list, coalescing adjacent free ranges.
+    /// The block itself is kept around for reuse; blocks are only freed when the `Allocator` is.
+    pub fn free(&mut self, allocation: Allocation) {
+        let block = self
+            .blocks
+            .iter_mut()
+            .find(|b| b.memory == allocation.memory)
+            .check_err("find block for freed allocation");
+
+        block.free_ranges.push((allocation.offset, allocation.size));
+        block.free_ranges.sort_by_key(|&(offset, _)| offset);
+        coalesce_free_ranges(&mut block.free_ranges);
+    }
+
+    fn alloc_from_existing_block(
+        &mut self,
+        memory_type_index: u32,
+        req: vk::MemoryRequirements,
+    ) -> Option<Allocation> {
+        for block in &mut self.blocks {
+            if block.memory_type_index != memory_type_index {
+                continue;
+            }
+
+            if let Some(offset) = carve_free_range(&mut block.free_ranges, req.size, req.alignment) {
+                return Some(Allocation {
+                    memory: block.memory,
+                    offset,
+                    size: req.size,
+                    block_mapped_ptr: block.mapped_ptr,
+                });
+            }
+        }
+
+        None
+    }
+
+    /// Creates a buffer and binds it to memory sub-allocated from a shared block instead of a
+    /// dedicated `vk::DeviceMemory`. Pass `extra_alignment` to fold in a constraint `alloc()`
+    /// doesn't see from `vk::MemoryRequirements` alone, e.g. `minUniformBufferOffsetAlignment` for
+    /// a uniform buffer or `nonCoherentAtomSize` for a non-coherent host-visible one; pass `1` if
+    /// none applies.
+    ///
+    /// Free with `destroy_buffer`, not `device.destroy_buffer` + `device.free_memory` — the latter
+    /// would free the whole block out from under every other buffer sub-allocated from it.
+    pub fn create_buffer(
+        &mut self,
+        size: u64,
+        usage: vk::BufferUsageFlags,
+        properties: vk::MemoryPropertyFlags,
+        extra_alignment: u64,
+    ) -> (vk::Buffer, Allocation) {
+        let create_info = vk::BufferCreateInfo {
+            size,
+            usage,
+            sharing_mode: vk::SharingMode::EXCLUSIVE,
+            ..Default::default()
+        };
+
+        let buffer =
+            unsafe { self.device.create_buffer(&create_info, None) }.check_err("create buffer");
+
+        let mut req = unsafe { self.device.get_buffer_memory_requirements(buffer) };
+        req.alignment = req.alignment.max(extra_alignment);
+
+        let allocation = self.alloc(req, properties);
+
+        unsafe { self.device.bind_buffer_memory(buffer, allocation.memory(), allocation.offset()) }
+            .check_err("bind buffer");
+
+        (buffer, allocation)
+    }
+
+    /// Destroys a buffer created by `create_buffer` and releases its sub-allocation.
+    pub fn destroy_buffer(&mut self, buffer: vk::Buffer, allocation: Allocation) {
+        unsafe {
+            self.device.destroy_buffer(buffer, None);
+        }
+
+        self.free(allocation);
+    }
+
+    fn alloc_new_block(
+        &mut self,
+        memory_type_index: u32,
+        req: vk::MemoryRequirements,
+        properties: vk::MemoryPropertyFlags,
+    ) -> Allocation {
+        let size = BLOCK_SIZE.max(req.size);
+
+        let alloc_info = vk::MemoryAllocateInfo {
+            allocation_size: size,
+            memory_type_index,
+            ..Default::default()
+        };
+
+        let memory = unsafe { self.device.allocate_memory(&alloc_info, None) }
+            .check_err("allocate memory block");
+
+        let mapped_ptr = if properties.contains(vk::MemoryPropertyFlags::HOST_VISIBLE) {
+            let ptr = unsafe {
+                self.device.map_memory(memory, 0, vk::WHOLE_SIZE, vk::MemoryMapFlags::empty())
+            }
+            .check_err("map memory block");
+
+            Some(ptr.cast::<u8>())
+        } else {
+            None
+        };
+
+        let mut free_ranges = vec![(0, size)];
+        let offset = carve_free_range(&mut free_ranges, req.size, req.alignment)
+            .check_err("carve first allocation out of fresh block");
+
+        self.blocks.push(Block {
+            memory,
+            memory_type_index,
+            mapped_ptr,
+            free_ranges,
+        });
+
+        Allocation {
+            memory,
+            offset,
+            size: req.size,
+            block_mapped_ptr: mapped_ptr,
+        }
+    }
+}
+
+impl Drop for Allocator {
+    fn drop(&mut self) {
+        unsafe {
+            for block in &self.blocks {
+                if block.mapped_ptr.is_some() {
+                    self.device.unmap_memory(block.memory);
+                }
+
+                self.device.free_memory(block.memory, None);
+            }
+        }
+    }
+}
+
+/// Finds the first free range that fits `size` bytes aligned to `alignment`, splits off the
+/// used portion (and any alignment padding before it) back into the free list, and returns the
+/// aligned offset. `None` if no range in `free_ranges` is big enough.
+fn carve_free_range(free_ranges: &mut Vec<(u64, u64)>, size: u64, alignment: u64) -> Option<u64> {
+    let index = free_ranges.iter().position(|&(range_offset, range_size)| {
+        let aligned_offset = range_offset.next_multiple_of(alignment);
+        aligned_offset - range_offset + size <= range_size
+    })?;
+
+    let (range_offset, range_size) = free_ranges.remove(index);
+    let aligned_offset = range_offset.next_multiple_of(alignment);
+    let padding = aligned_offset - range_offset;
+    let range_end = range_offset + range_size;
+    let used_end = aligned_offset + size;
+
+    if padding > 0 {
+        free_ranges.push((range_offset, padding));
+    }
+
+    if used_end < range_end {
+        free_ranges.push((used_end, range_end - used_end));
+    }
+
+    Some(aligned_offset)
+}
+
+fn coalesce_free_ranges(free_ranges: &mut Vec<(u64, u64)>) {
+    let mut i = 0;
+
+    while i + 1 < free_ranges.len() {
+        let (offset, size) = free_ranges[i];
+        let (next_offset, next_size) = free_ranges[i + 1];
+
+        if offset + size == next_offset {
+            free_ranges[i] = (offset, size + next_size);
+            free_ranges.remove(i + 1);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn offset_usize(offset: u64) -> usize {
+    offset as usize
+}
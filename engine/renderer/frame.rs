@@ -0,0 +1,135 @@
+use ash::vk;
+
+use super::*;
+
+/// One step queued onto a `Frame`, turned into Vulkan calls by `Frame::record`.
+pub enum DrawCommand {
+    SetViewport(vk::Viewport),
+    SetScissor(vk::Rect2D),
+    Draw {
+        mesh_index: usize,
+        uniforms: Option<UniformBufferType>,
+        instances: Option<Vec<InstanceData>>,
+    },
+}
+
+impl DrawCommand {
+    /// Overrides this draw's first uniform buffer (`MeshData::uniform_buffer_mut(0)`) for this
+    /// frame only, instead of the mesh needing its own `set_update_data_cb` closure to mutate and
+    /// copy it every frame.
+    pub fn with_uniforms(&mut self, uniforms: UniformBufferType) -> &mut Self {
+        if let Self::Draw { uniforms: slot, .. } = self {
+            *slot = Some(uniforms);
+        }
+
+        self
+    }
+
+    /// Overrides this draw's instance data for this frame only, the command-list counterpart to
+    /// calling `MeshData::update_instances` directly.
+    pub fn with_instances(&mut self, instances: Vec<InstanceData>) -> &mut Self {
+        if let Self::Draw { instances: slot, .. } = self {
+            *slot = Some(instances);
+        }
+
+        self
+    }
+}
+
+/// A retained list of `DrawCommand`s, built up once per frame by `set_viewport`/`set_scissor`/
+/// `draw` and only turned into actual `vkCmd*` calls by `record`, instead of each mesh owning a
+/// `set_update_data_cb` closure that mutates its own UBO and copies it as a side effect of
+/// `Renderer::update_data`'s independent per-mesh loop. Meshes are referenced by their index into
+/// the `&mut [MeshData]` slice passed to `record` (the same slice `Renderer::meshes` already
+/// holds), since nothing else in this tree hands out a stable mesh handle/ID a `Frame` could store
+/// instead.
+///
+/// This is a self-contained alternative recording path, not yet wired into
+/// `Renderer::record_commands`/`Renderer::update_data`: switching those over means replacing every
+/// existing `MeshDataBuilder::build`/`set_update_data_cb` call site's scene-setup code in `mod.rs`
+/// with `Frame::draw` calls instead, which is a larger rework of how `create_meshes` wires up scene
+/// state than fits in this change; that migration is left as a follow-up.
+#[derive(Default)]
+pub struct Frame {
+    commands: Vec<DrawCommand>,
+}
+
+impl Frame {
+    pub fn new() -> Self {
+        Self { commands: vec![] }
+    }
+
+    pub fn set_viewport(&mut self, viewport: vk::Viewport) -> &mut Self {
+        self.commands.push(DrawCommand::SetViewport(viewport));
+        self
+    }
+
+    pub fn set_scissor(&mut self, scissor: vk::Rect2D) -> &mut Self {
+        self.commands.push(DrawCommand::SetScissor(scissor));
+        self
+    }
+
+    /// Queues a draw of `meshes[mesh_index]` (the slice `record` will later be given). Chain
+    /// `.with_uniforms`/`.with_instances` on the returned command to override what would otherwise
+    /// have been left from the previous frame.
+    pub fn draw(&mut self, mesh_index: usize) -> &mut DrawCommand {
+        self.commands.push(DrawCommand::Draw {
+            mesh_index,
+            uniforms: None,
+            instances: None,
+        });
+
+        self.commands.last_mut().unwrap_or_else(|| unreachable!())
+    }
+
+    /// Empties the command list, so the same `Frame` can be rebuilt fresh next frame instead of
+    /// being reallocated.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Turns every queued command into Vulkan calls. Consecutive `Draw`s whose meshes have the
+    /// same pipeline handle only bind it once, via `MeshData::record_draw_commands_batched`,
+    /// instead of each draw rebinding it independently. Each `MeshData` owns its own pipeline, so
+    /// this only happens when the same `mesh_index` is drawn twice in a row.
+    pub unsafe fn record(
+        &self,
+        device: &ash::Device,
+        meshes: &mut [MeshData],
+        cmd_buffer: vk::CommandBuffer,
+        current_frame: usize,
+    ) {
+        let mut last_pipeline = None;
+
+        for command in &self.commands {
+            match command {
+                DrawCommand::SetViewport(viewport) => {
+                    device.cmd_set_viewport(cmd_buffer, 0, &[*viewport]);
+                }
+                DrawCommand::SetScissor(scissor) => {
+                    device.cmd_set_scissor(cmd_buffer, 0, &[*scissor]);
+                }
+                DrawCommand::Draw { mesh_index, uniforms, instances } => {
+                    let mesh = &mut meshes[*mesh_index];
+
+                    if let Some(u) = uniforms {
+                        if let Some(slot) = mesh.uniform_buffer_mut(0) {
+                            *slot = *u;
+                        }
+
+                        mesh.copy_to_uniform_mapping(current_frame);
+                    }
+
+                    if let Some(instances) = instances {
+                        mesh.update_instances(instances, current_frame);
+                    }
+
+                    let pipeline =
+                        mesh.record_draw_commands_batched(cmd_buffer, current_frame, last_pipeline);
+
+                    last_pipeline = Some(pipeline);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use ash::vk;
+
+use super::*;
+use crate::utils::*;
+
+/// Measures real GPU execution time of labeled command buffer regions via timestamp queries. One
+/// query pool is kept per in-flight frame so that resolving frame N-1's timings doesn't have to
+/// wait on frame N's queries landing in the same pool.
+pub struct GpuTimer {
+    device: ash::Device,
+    pools: Vec<vk::QueryPool>,
+    timestamp_period: f32,
+    /// Mask applied to raw query results; queue families only guarantee the low
+    /// `timestamp_valid_bits` bits of a timestamp are meaningful.
+    valid_bits_mask: u64,
+    labels: Vec<&'static str>,
+}
+
+impl GpuTimer {
+    pub fn new(
+        device: &ash::Device,
+        timestamp_period: f32,
+        timestamp_valid_bits: u32,
+        frames_in_flight: usize,
+        labels: &[&'static str],
+    ) -> Self {
+        let create_info = vk::QueryPoolCreateInfo {
+            query_type: vk::QueryType::TIMESTAMP,
+            query_count: to_u32(labels.len() * 2),
+            ..Default::default()
+        };
+
+        let pools = (0..frames_in_flight)
+            .map(|_| {
+                unsafe { device.create_query_pool(&create_info, None) }
+                    .check_err("create query pool")
+            })
+            .collect();
+
+        Self {
+            device: device.clone(),
+            pools,
+            timestamp_period,
+            valid_bits_mask: valid_bits_mask(timestamp_valid_bits),
+            labels: labels.to_vec(),
+        }
+    }
+
+    pub unsafe fn reset(&self, cmd: vk::CommandBuffer, frame: usize) {
+        self.device.cmd_reset_query_pool(cmd, self.pools[frame], 0, to_u32(self.labels.len() * 2));
+    }
+
+    pub unsafe fn begin_scope(&self, cmd: vk::CommandBuffer, frame: usize, label: &'static str) {
+        let query = self.slot(label) * 2;
+        self.write_timestamp(cmd, vk::PipelineStageFlags::TOP_OF_PIPE, frame, query);
+    }
+
+    pub unsafe fn end_scope(&self, cmd: vk::CommandBuffer, frame: usize, label: &'static str) {
+        let query = self.slot(label) * 2 + 1;
+        self.write_timestamp(cmd, vk::PipelineStageFlags::BOTTOM_OF_PIPE, frame, query);
+    }
+
+    /// Records a timestamp for an arbitrary query slot once `stage` has completed. `begin_scope`/
+    /// `end_scope` cover the common case of bracketing a labeled region; use this directly to
+    /// place a timestamp at some other point in the pipeline.
+    pub unsafe fn write_timestamp(
+        &self,
+        cmd: vk::CommandBuffer,
+        stage: vk::PipelineStageFlags,
+        frame: usize,
+        query_index: u32,
+    ) {
+        self.device.cmd_write_timestamp(cmd, stage, self.pools[frame], query_index);
+    }
+
+    /// Returns each label's duration in milliseconds for `frame`, in the order passed to `new()`.
+    pub fn resolve(&self, frame: usize) -> Vec<(&'static str, f64)> {
+        let mut data = vec![0u64; self.labels.len() * 2];
+
+        unsafe {
+            self.device.get_query_pool_results(
+                self.pools[frame],
+                0,
+                &mut data,
+                vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT,
+            )
+        }
+        .check_err("get query pool results");
+
+        self.labels
+            .iter()
+            .enumerate()
+            .map(|(i, &label)| {
+                let begin = data[i * 2] & self.valid_bits_mask;
+                let end = data[i * 2 + 1] & self.valid_bits_mask;
+                let ms = (end - begin) as f64 * f64::from(self.timestamp_period) / 1_000_000.0;
+
+                (label, ms)
+            })
+            .collect()
+    }
+
+    fn slot(&self, label: &'static str) -> u32 {
+        let index = self.labels.iter().position(|&l| l == label);
+
+        to_u32(index.check_err("find GPU timer label"))
+    }
+}
+
+/// Mask selecting the low `valid_bits` bits of a timestamp, per `timestampValidBits`'s semantics
+/// (0 means the queue family doesn't support timestamps at all).
+fn valid_bits_mask(valid_bits: u32) -> u64 {
+    if valid_bits >= 64 {
+        u64::MAX
+    } else {
+        (1u64 << valid_bits) - 1
+    }
+}
+
+impl Drop for GpuTimer {
+    fn drop(&mut self) {
+        unsafe {
+            for &pool in &self.pools {
+                self.device.destroy_query_pool(pool, None);
+            }
+        }
+    }
+}
+
+/// Counts shader invocations of a labeled command buffer region via a pipeline statistics query,
+/// one query pool per in-flight frame (same rationale as `GpuTimer`). Only ever constructed when
+/// `PhysicalDeviceFeatures::pipeline_statistics_query` is actually supported by the device; callers
+/// otherwise fall back to `None` rather than holding a `PipelineStatsQuery` that can't be used.
+pub struct PipelineStatsQuery {
+    device: ash::Device,
+    pools: Vec<vk::QueryPool>,
+}
+
+impl PipelineStatsQuery {
+    pub fn new(device: &ash::Device, frames_in_flight: usize) -> Self {
+        let create_info = vk::QueryPoolCreateInfo {
+            query_type: vk::QueryType::PIPELINE_STATISTICS,
+            query_count: 1,
+            pipeline_statistics: vk::QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS,
+            ..Default::default()
+        };
+
+        let pools = (0..frames_in_flight)
+            .map(|_| {
+                unsafe { device.create_query_pool(&create_info, None) }
+                    .check_err("create pipeline stats query pool")
+            })
+            .collect();
+
+        Self { device: device.clone(), pools }
+    }
+
+    pub unsafe fn begin(&self, cmd: vk::CommandBuffer, frame: usize) {
+        self.device.cmd_reset_query_pool(cmd, self.pools[frame], 0, 1);
+        self.device.cmd_begin_query(cmd, self.pools[frame], 0, vk::QueryControlFlags::empty());
+    }
+
+    pub unsafe fn end(&self, cmd: vk::CommandBuffer, frame: usize) {
+        self.device.cmd_end_query(cmd, self.pools[frame], 0);
+    }
+
+    /// The dispatch's invocation count for `frame`, read back after its frame fence is signaled.
+    pub fn resolve(&self, frame: usize) -> u64 {
+        let mut data = [0u64; 1];
+
+        unsafe {
+            self.device.get_query_pool_results(
+                self.pools[frame],
+                0,
+                &mut data,
+                vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT,
+            )
+        }
+        .check_err("get pipeline stats query pool results");
+
+        data[0]
+    }
+}
+
+impl Drop for PipelineStatsQuery {
+    fn drop(&mut self) {
+        unsafe {
+            for &pool in &self.pools {
+                self.device.destroy_query_pool(pool, None);
+            }
+        }
+    }
+}
@@ -1,18 +1,56 @@
-use std::mem::size_of;
+use std::collections::HashMap;
+use std::mem::{size_of, size_of_val};
+use std::path::Path;
+use std::process::Command;
 
+use anyhow::{anyhow, ensure, Result};
 use ash::vk;
+use glam::Mat3;
 
 use super::pipeline::*;
 use super::vulkan::*;
 use super::*;
+use crate::repr_enum;
 use crate::utils::*;
 
 pub struct Mesh {
     vertices: Vec<f32>,
-    indices: Vec<u16>,
+    indices: Indices,
     builder: PipelineModifier,
 }
 
+/// A mesh's index buffer contents, either `u16` (the common case, and the only option before
+/// `Mesh::from_obj` could produce more than 65,535 unique vertices) or `u32` for meshes that
+/// exceed that, so the render pass index buffer stays byte-for-byte valid either way.
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    fn len(&self) -> usize {
+        match self {
+            Self::U16(v) => v.len(),
+            Self::U32(v) => v.len(),
+        }
+    }
+
+    const fn vk_index_type(&self) -> vk::IndexType {
+        match self {
+            Self::U16(_) => vk::IndexType::UINT16,
+            Self::U32(_) => vk::IndexType::UINT32,
+        }
+    }
+}
+
+/// One sub-mesh out of `Mesh::from_iqm`, paired with the material name its `iqmmesh` entry
+/// resolved to, so the caller can pick a texture/shader per sub-mesh before the usual
+/// `.to_builder(...).build()` chain.
+pub struct IqmMesh {
+    pub material: String,
+    pub mesh: Mesh,
+}
+
 pub struct MeshDataBuilder<'m, 'p> {
     mesh: &'m Mesh,
     device: ash::Device,
@@ -27,6 +65,11 @@ pub struct MeshDataBuilder<'m, 'p> {
     shader_attachments: Vec<ShaderAttachment<'p>>,
     push_consts: Option<PushConstants>,
     builder: Option<PipelineModifier>,
+    pipeline_cache: Option<&'p PipelineCache>,
+    instances: Option<Vec<InstanceData>>,
+    shader_names: Option<(&'static str, &'static str)>,
+    multiview: Option<u32>,
+    gpu_timer: Option<(f32, u32)>,
 }
 
 pub struct MeshData {
@@ -36,11 +79,23 @@ pub struct MeshData {
     index_buffer: vk::Buffer,
     index_buffer_memory: vk::DeviceMemory,
     index_count: u32,
+    index_type: vk::IndexType,
     pipeline: Pipeline,
     push_consts: Option<PushConstants>,
     uniform_buffer: Option<UniformBuffer>,
     update_data: Option<UpdateDataCb>,
     advances_subpass: bool,
+    instance_buffers: Vec<vk::Buffer>,
+    instance_buffer_memories: Vec<vk::DeviceMemory>,
+    instance_mappings: Vec<*mut InstanceData>,
+    instance_capacity: usize,
+    instance_count: u32,
+    render_pass: vk::RenderPass,
+    mesh_builder: PipelineModifier,
+    extra_builder: Option<PipelineModifier>,
+    pipeline_cache: vk::PipelineCache,
+    shader_names: Option<(&'static str, &'static str)>,
+    gpu_timer: Option<GpuTimer>,
 }
 
 pub struct ComputeTarget {
@@ -66,6 +121,10 @@ pub struct ComputeTarget {
     local_size_x: u32,
     local_size_y: u32,
     clear_color: bool,
+    pipeline_cache: vk::PipelineCache,
+    shader_name: Option<&'static str>,
+    gpu_timer: Option<GpuTimer>,
+    stats_query: Option<PipelineStatsQuery>,
 }
 
 struct UniformBuffer {
@@ -73,7 +132,10 @@ struct UniformBuffer {
     desc_set_layout: vk::DescriptorSetLayout,
     desc_pool: vk::DescriptorPool,
     desc_sets: Vec<vk::DescriptorSet>,
-    buf_mem: Option<BufferMemory>,
+    /// One entry per `ShaderAttachment::UniformBuffer` passed to `with_uniform_buffer`, in the
+    /// order they were added (so also in ascending binding order, since bindings are assigned by
+    /// attachment order too).
+    buf_mems: Vec<BufferMemory>,
 }
 
 struct BufferMemory {
@@ -89,8 +151,24 @@ struct ComputeBufferReadOnlyMemory {
     device: ash::Device,
     buffer: vk::Buffer,
     memory: vk::DeviceMemory,
-    mapping: *mut u32,
     size: u64,
+    backing: ComputeBufferBacking,
+}
+
+/// How a `ComputeBufferReadOnlyMemory` gets its data from the CPU: either a persistently-mapped
+/// host-visible allocation that `upload` writes with a plain `memcpy` (cheap enough to call every
+/// frame, e.g. a ray-cast shader's per-frame camera-dependent data), or device-local memory
+/// written via a one-off staging-buffer copy instead (faster for the compute shader to read back,
+/// at the cost of a command-buffer round trip per `upload`, so callers should gate calls on their
+/// own dirty tracking the way `World::needs_upload`/`uploaded` already does for `worldSizes`/
+/// `worldSpans`, rather than calling `upload` unconditionally every frame).
+enum ComputeBufferBacking {
+    HostMapped(*mut u32),
+    DeviceLocal {
+        device_mem_properties: vk::PhysicalDeviceMemoryProperties,
+        command_pool: vk::CommandPool,
+        queue: vk::Queue,
+    },
 }
 
 pub enum ShaderAttachment<'t> {
@@ -98,11 +176,111 @@ pub enum ShaderAttachment<'t> {
     Texture(&'t Texture),
     Textures(&'t [Texture]),
     InputAttachment(&'t FramebufferAttachment),
+    /// A top-level acceleration structure, for a fragment shader that ray-queries it directly
+    /// (`GL_EXT_ray_query`) rather than going through a separate `RayTracePipeline`/SBT, e.g. to
+    /// sample ray-traced shadows or reflections while shading a regular rasterized `MeshData`.
+    AccelerationStructure(&'t AccelerationStructureHandle),
+    /// A bindless array of `N` textures in a single binding (e.g. one draw's worth of materials),
+    /// meant to be indexed dynamically in the shader (a push-constant material index) rather than
+    /// bound per-draw. Needs `VK_EXT_descriptor_indexing`'s `PARTIALLY_BOUND`/`UPDATE_AFTER_BIND`
+    /// binding flags, which this variant's own binding requests, but the matching device feature
+    /// (`shaderSampledImageArrayNonUniformIndexing` plus the two binding-flag features) still
+    /// needs enabling at device creation — not done here, the same follow-up `ray_trace.rs`
+    /// already leaves for `VK_KHR_acceleration_structure`.
+    TextureArray(&'t [Texture]),
+}
+
+/// One binding for a `ComputePipelineBuilder`, the compute-shader counterpart of
+/// `ShaderAttachment`: either a single resource reused every frame, or one slice entry per frame
+/// in flight (e.g. `StorageBuffers` over a `MeshData`'s own per-frame instance buffers, for a
+/// culling pass that writes positions the instanced draw path then reads).
+pub enum ComputeAttachment<'t> {
+    StorageBuffer(vk::Buffer, u64),
+    StorageBuffers(&'t [(vk::Buffer, u64)]),
+    StorageImage(&'t Texture),
+    StorageImages(&'t [Texture]),
+}
+
+/// Builder for a standalone compute pipeline with storage-buffer/image bindings, the generic
+/// counterpart to `ComputeTarget`'s fixed four-binding raycasting layout on its own compute queue:
+/// this assembles whatever bindings `with_storage_buffer`/`with_storage_image` were given and
+/// hands back a `ComputePipeline` ready to `dispatch` on the caller's own command buffer (typically
+/// the graphics queue's, recorded before the render pass starts).
+pub struct ComputePipelineBuilder<'p> {
+    device: ash::Device,
+    shader_compiled: &'p [u8],
+    per_frame_copies: usize,
+    attachments: Vec<ComputeAttachment<'p>>,
+    push_const_range: Option<vk::PushConstantRange>,
+    pipeline_cache: vk::PipelineCache,
+}
+
+pub struct ComputePipeline {
+    device: ash::Device,
+    pipeline: Pipeline,
+    desc_set_layout: vk::DescriptorSetLayout,
+    desc_pool: vk::DescriptorPool,
+    desc_sets: Vec<vk::DescriptorSet>,
 }
 
 #[derive(Clone, Copy)]
 pub enum UniformBufferType {
     ModelViewProj(ModelViewProjUBO),
+    /// Camera view/projection plus its world-space position, for shaders (specular highlights,
+    /// PBR) that need the eye position rather than just the matrices in `ModelViewProj`.
+    CameraView(CameraViewUBO),
+    /// Both eyes' view/projection pairs, for a mesh drawn into a multiview subpass (see
+    /// `SubpassBuilder::with_view_mask`) once its shader indexes `eyes` by `gl_ViewIndex`.
+    StereoCamera(StereoCameraUBO),
+}
+
+/// Implemented by every struct usable as a `UniformBufferType` variant's payload: asserts that
+/// its `repr(C)` layout already matches GLSL's `layout(std140)` rules (`vec3`/`mat3` fields
+/// rounded up to 16-byte alignment, struct size rounded up to a multiple of 16 bytes), so
+/// `copy_to_uniform_mapping`'s raw `memcpy` into the mapped buffer is safe. A struct made purely
+/// of `Mat4`/`Vec4`/`Std140Vec3`/`Std140Mat3` fields (every one of which is already 16-byte
+/// aligned) satisfies this for free; one with a bare `Vec3` or `Mat3` field needs `Std140Vec3`/
+/// `Std140Mat3` in its place instead, the same way `CameraViewUBO` uses `Std140Vec3` for
+/// `world_position`.
+pub trait AsStd140 {
+    fn std140_size() -> usize;
+}
+
+/// A `Vec3` padded to `vec3`'s std140 alignment (16 bytes), for uniform struct fields that would
+/// otherwise need a hand-written trailing `_pad: f32` (see `RayCastPushConstants` for the old,
+/// easy-to-get-wrong-when-reordered way of doing this).
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct Std140Vec3 {
+    pub value: Vec3,
+    _pad: f32,
+}
+
+impl Std140Vec3 {
+    pub const fn new(value: Vec3) -> Self {
+        Self { value, _pad: 0.0 }
+    }
+}
+
+/// A `Mat3` laid out the std140 way: as three `Std140Vec3` columns, each padded out to 16 bytes
+/// (std140 has no native 12-byte-wide type, so a plain `[[f32; 3]; 3]` or glam's packed `Mat3`
+/// both disagree with what a `layout(std140) mat3` actually reads).
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Std140Mat3 {
+    columns: [Std140Vec3; 3],
+}
+
+impl Std140Mat3 {
+    pub fn new(m: Mat3) -> Self {
+        Self {
+            columns: [
+                Std140Vec3::new(m.x_axis),
+                Std140Vec3::new(m.y_axis),
+                Std140Vec3::new(m.z_axis),
+            ],
+        }
+    }
 }
 
 pub struct PushConstants {
@@ -126,7 +304,7 @@ impl Mesh {
     pub fn screen_rect() -> Self {
         Self {
             vertices: vec![-1.0, 1.0, 1.0, 1.0, 1.0, -1.0, -1.0, -1.0],
-            indices: vec![0, 1, 2, 2, 3, 0],
+            indices: Indices::U16(vec![0, 1, 2, 2, 3, 0]),
             builder: |b| b.with_2_vertices(),
         }
     }
@@ -170,7 +348,7 @@ impl Mesh {
 
         Self {
             vertices,
-            indices,
+            indices: Indices::U16(indices),
             builder: |b| b.with_topology(vk::PrimitiveTopology::LINE_LIST).with_2_vertices(),
         }
     }
@@ -210,7 +388,7 @@ impl Mesh {
 
         Self {
             vertices,
-            indices,
+            indices: Indices::U16(indices),
             builder: |b| b.with_topology(vk::PrimitiveTopology::LINE_LIST).with_2_vertices(),
         }
     }
@@ -256,7 +434,7 @@ impl Mesh {
 
         Self {
             vertices,
-            indices,
+            indices: Indices::U16(indices),
             builder: |b| {
                 let pos_desc = vk::VertexInputAttributeDescription {
                     location: 0,
@@ -296,7 +474,7 @@ impl Mesh {
 
         Self {
             vertices,
-            indices,
+            indices: Indices::U16(indices),
             builder: |b| b.with_topology(vk::PrimitiveTopology::LINE_LIST).with_3_vertices(),
         }
     }
@@ -314,7 +492,7 @@ impl Mesh {
 
         Self {
             vertices,
-            indices,
+            indices: Indices::U16(indices),
             builder: |b| {
                 let pos_desc = vk::VertexInputAttributeDescription {
                     location: 0,
@@ -349,7 +527,7 @@ impl Mesh {
 
         Self {
             vertices,
-            indices,
+            indices: Indices::U16(indices),
             builder: |b| {
                 let pos_desc = vk::VertexInputAttributeDescription {
                     location: 0,
@@ -384,7 +562,7 @@ impl Mesh {
 
         Self {
             vertices,
-            indices,
+            indices: Indices::U16(indices),
             builder: |b| {
                 let pos_desc = vk::VertexInputAttributeDescription {
                     location: 0,
@@ -408,6 +586,230 @@ impl Mesh {
         }
     }
 
+    /// Loads a triangle mesh from an OBJ file via `tobj`, deduplicating each unique
+    /// position/normal/texcoord triple into one vertex (so two faces meeting at different angles
+    /// still get their own copies of the shared corner, rather than averaging across it), and
+    /// synthesizing a flat per-face normal when the file doesn't supply one. Feeds the same
+    /// `to_builder(...).build()` path as the procedural generators above, so loaded models get the
+    /// same UBO/push-constant/texture wiring.
+    pub fn from_obj(path: &'static str) -> Result<Self> {
+        let load_options = tobj::LoadOptions {
+            triangulate: true,
+            single_index: false,
+            ..Default::default()
+        };
+
+        let (models, _materials) = tobj::load_obj(path, &load_options)?;
+
+        let mut vertices: Vec<f32> = vec![];
+        let mut indices: Vec<u32> = vec![];
+
+        for model in &models {
+            // Each model indexes its own position/normal/texcoord arrays, so index triples are only
+            // comparable within one model.
+            let mut seen: HashMap<(u32, u32, u32), u32> = HashMap::new();
+            let mesh = &model.mesh;
+            let has_normals = !mesh.normals.is_empty();
+            let has_texcoords = !mesh.texcoords.is_empty();
+            let flat_normals = (!has_normals).then(|| flat_face_normals(mesh));
+
+            for (face, &pos_idx) in mesh.indices.iter().enumerate() {
+                let normal_idx =
+                    if has_normals { mesh.normal_indices[face] } else { to_u32(face / 3) };
+                let texcoord_idx = if has_texcoords { mesh.texcoord_indices[face] } else { 0 };
+
+                let key = (pos_idx, normal_idx, texcoord_idx);
+                let next_index = to_u32(vertices.len() / 8);
+
+                let index = *seen.entry(key).or_insert_with(|| {
+                    let p = pos_idx as usize * 3;
+                    vertices.extend_from_slice(&mesh.positions[p..p + 3]);
+
+                    match &flat_normals {
+                        Some(ns) => vertices.extend_from_slice(&ns[face / 3]),
+                        None => {
+                            let n = normal_idx as usize * 3;
+                            vertices.extend_from_slice(&mesh.normals[n..n + 3]);
+                        }
+                    }
+
+                    if has_texcoords {
+                        let t = texcoord_idx as usize * 2;
+                        vertices.extend_from_slice(&mesh.texcoords[t..t + 2]);
+                    } else {
+                        vertices.extend_from_slice(&[0.0, 0.0]);
+                    }
+
+                    next_index
+                });
+
+                indices.push(index);
+            }
+        }
+
+        let vertex_count = vertices.len() / 8;
+        let indices = if vertex_count <= usize::from(u16::MAX) {
+            Indices::U16(indices.into_iter().map(|i| to_u16(i as usize)).collect())
+        } else {
+            Indices::U32(indices)
+        };
+
+        Ok(Self {
+            vertices,
+            indices,
+            builder: |b| {
+                let pos_desc = vk::VertexInputAttributeDescription {
+                    location: 0,
+                    binding: 0,
+                    format: vk::Format::R32G32B32_SFLOAT,
+                    offset: 0,
+                };
+
+                let normal_desc = vk::VertexInputAttributeDescription {
+                    location: 1,
+                    binding: 0,
+                    format: vk::Format::R32G32B32_SFLOAT,
+                    offset: 3 * SIZE_F32,
+                };
+
+                let uv_desc = vk::VertexInputAttributeDescription {
+                    location: 2,
+                    binding: 0,
+                    format: vk::Format::R32G32_SFLOAT,
+                    offset: 6 * SIZE_F32,
+                };
+
+                b.with_topology(vk::PrimitiveTopology::TRIANGLE_LIST)
+                    .with_stride_in_f32s(8)
+                    .add_vertex_desc(pos_desc)
+                    .add_vertex_desc(normal_desc)
+                    .add_vertex_desc(uv_desc)
+            },
+        })
+    }
+
+    /// Loads every sub-mesh from an Inter-Quake Model (`.iqm`) file at `path`. See
+    /// `from_iqm_bytes` for the format.
+    pub fn from_iqm(path: &'static str) -> Result<Vec<IqmMesh>> {
+        let bytes = std::fs::read(path)?;
+
+        Self::from_iqm_bytes(&bytes)
+    }
+
+    /// Parses an IQM binary blob (header `"INTERQUAKEMODEL\0"` + version, then offset/count pairs
+    /// into the rest of the file) into one `Mesh` per `iqmmesh` entry, each paired with its
+    /// material name resolved from the text blob. Reads the `POSITION`/`NORMAL`/`TEXCOORD` vertex
+    /// arrays (interleaving them into this crate's usual `[pos, normal, uv]` layout, same as
+    /// `from_obj`) and the `iqmtriangle` table, re-based per sub-mesh so each comes out as its own
+    /// independently indexed `Mesh`. IQM's `BLENDINDEXES`/`BLENDWEIGHTS` arrays (skeletal skinning
+    /// weights) and its joint/pose/animation sections are read past but otherwise ignored: nothing
+    /// in this renderer consumes bone matrices today, so wiring them up is out of scope here.
+    pub fn from_iqm_bytes(bytes: &[u8]) -> Result<Vec<IqmMesh>> {
+        let header = IqmHeader::parse(bytes)?;
+
+        let positions = iqm_vertex_array(bytes, &header, IqmVertexArrayType::Position, 3)?
+            .ok_or_else(|| anyhow!("IQM file has no POSITION vertex array"))?;
+        let normals = iqm_vertex_array(bytes, &header, IqmVertexArrayType::Normal, 3)?;
+        let texcoords = iqm_vertex_array(bytes, &header, IqmVertexArrayType::TexCoord, 2)?;
+
+        let mut out = Vec::with_capacity(header.num_meshes as usize);
+
+        for i in 0..header.num_meshes as usize {
+            let entry_off = header.ofs_meshes as usize + i * IQM_MESH_SIZE;
+
+            let material_offset = iqm_u32(bytes, entry_off + 4)?;
+            let first_vertex = iqm_u32(bytes, entry_off + 8)? as usize;
+            let num_vertexes = iqm_u32(bytes, entry_off + 12)? as usize;
+            let first_triangle = iqm_u32(bytes, entry_off + 16)? as usize;
+            let num_triangles = iqm_u32(bytes, entry_off + 20)? as usize;
+
+            let material = iqm_text(bytes, &header, material_offset)?;
+
+            let vertex_end = first_vertex.checked_add(num_vertexes).ok_or_else(|| {
+                anyhow!("IQM mesh vertex range ({first_vertex}, {num_vertexes}) overflows")
+            })?;
+            ensure!(
+                vertex_end <= header.num_vertexes as usize,
+                "IQM mesh vertex range {first_vertex}..{vertex_end} exceeds the file's \
+                 {} vertices",
+                header.num_vertexes
+            );
+
+            let mut vertices = Vec::with_capacity(num_vertexes * 8);
+
+            for v in first_vertex..vertex_end {
+                vertices.extend_from_slice(iqm_slice(&positions, v * 3, 3)?);
+
+                match &normals {
+                    Some(n) => vertices.extend_from_slice(iqm_slice(n, v * 3, 3)?),
+                    None => vertices.extend_from_slice(&[0.0, 0.0, 0.0]),
+                }
+
+                match &texcoords {
+                    Some(t) => vertices.extend_from_slice(iqm_slice(t, v * 2, 2)?),
+                    None => vertices.extend_from_slice(&[0.0, 0.0]),
+                }
+            }
+
+            let mut indices = Vec::with_capacity(num_triangles * 3);
+
+            for t in first_triangle..first_triangle + num_triangles {
+                let tri_off = header.ofs_triangles as usize + t * IQM_TRIANGLE_SIZE;
+
+                for corner in 0..3 {
+                    let vertex = iqm_u32(bytes, tri_off + corner * 4)? as usize;
+                    let local = vertex.checked_sub(first_vertex).ok_or_else(|| {
+                        anyhow!("IQM triangle vertex {vertex} precedes its mesh's first vertex")
+                    })?;
+                    ensure!(
+                        vertex < vertex_end,
+                        "IQM triangle vertex {vertex} is past its mesh's last vertex {}",
+                        vertex_end - 1
+                    );
+
+                    indices.push(to_u16(local));
+                }
+            }
+
+            let mesh = Self {
+                vertices,
+                indices: Indices::U16(indices),
+                builder: |b| {
+                    let pos_desc = vk::VertexInputAttributeDescription {
+                        location: 0,
+                        binding: 0,
+                        format: vk::Format::R32G32B32_SFLOAT,
+                        offset: 0,
+                    };
+
+                    let normal_desc = vk::VertexInputAttributeDescription {
+                        location: 1,
+                        binding: 0,
+                        format: vk::Format::R32G32B32_SFLOAT,
+                        offset: 3 * SIZE_F32,
+                    };
+
+                    let uv_desc = vk::VertexInputAttributeDescription {
+                        location: 2,
+                        binding: 0,
+                        format: vk::Format::R32G32_SFLOAT,
+                        offset: 6 * SIZE_F32,
+                    };
+
+                    b.with_topology(vk::PrimitiveTopology::TRIANGLE_LIST)
+                        .with_stride_in_f32s(8)
+                        .add_vertex_desc(pos_desc)
+                        .add_vertex_desc(normal_desc)
+                        .add_vertex_desc(uv_desc)
+                },
+            };
+
+            out.push(IqmMesh { material, mesh });
+        }
+
+        Ok(out)
+    }
+
     pub fn to_builder<'m, 'p>(
         &'m self,
         device: &ash::Device,
@@ -433,6 +835,11 @@ impl Mesh {
             shader_attachments: vec![],
             push_consts: None,
             builder: None,
+            pipeline_cache: None,
+            instances: None,
+            shader_names: None,
+            multiview: None,
+            gpu_timer: None,
         }
     }
 }
@@ -448,6 +855,9 @@ impl<'m, 'p> MeshDataBuilder<'m, 'p> {
         self
     }
 
+    /// Adds another uniform buffer, bound at the next sequential binding index. Can be called more
+    /// than once per mesh (e.g. one `ModelViewProj` plus one `CameraView`); each gets its own
+    /// binding and its own `MeshData::uniform_buffer_mut` index, in call order.
     pub fn with_uniform_buffer(mut self, buf: UniformBufferType) -> Self {
         self.shader_attachments.push(ShaderAttachment::UniformBuffer(buf));
         self
@@ -468,6 +878,56 @@ impl<'m, 'p> MeshDataBuilder<'m, 'p> {
         self
     }
 
+    /// Binds a top-level acceleration structure for this mesh's fragment shader to ray-query
+    /// directly (see `ShaderAttachment::AccelerationStructure`).
+    pub fn with_acceleration_structure(mut self, accel: &'p AccelerationStructureHandle) -> Self {
+        self.shader_attachments.push(ShaderAttachment::AccelerationStructure(accel));
+        self
+    }
+
+    /// Binds `textures` as a single bindless array, dynamically indexable in the fragment shader
+    /// (see `ShaderAttachment::TextureArray`).
+    pub fn with_texture_array(mut self, textures: &'p [Texture]) -> Self {
+        self.shader_attachments.push(ShaderAttachment::TextureArray(textures));
+        self
+    }
+
+    /// Binds `instances` as a second, per-instance-rate vertex buffer, so `record_draw_commands`
+    /// renders `instances.len()` copies in a single `cmd_draw_indexed` call instead of one
+    /// `MeshData` per copy (e.g. thousands of voxel block transforms). The per-frame instance
+    /// buffers are sized to `instances.len()`; `MeshData::update_instances` can refill them with
+    /// up to that many instances afterwards, but never more.
+    pub fn with_instances(mut self, instances: Vec<InstanceData>) -> Self {
+        self.instances = Some(instances);
+        self
+    }
+
+    /// Marks this mesh as drawing into a multiview subpass built with `view_count` views (e.g.
+    /// `2` for a stereo pair, via `SubpassBuilder::with_view_mask(0b11)`), so a single
+    /// `cmd_draw_indexed` broadcasts across all of `render_pass`'s attachment layers instead of
+    /// one draw per view. This only checks the invariant the broadcast relies on — that any
+    /// `UniformBufferType::StereoCamera` attached to this mesh actually carries `view_count`
+    /// matrices, so per-view data doesn't alias — since the pipeline itself needs no extra state:
+    /// its subpass's `viewMask` already comes from whichever `render_pass` it's built against.
+    pub fn with_multiview(mut self, view_count: u32) -> Self {
+        self.multiview = Some(view_count);
+        self
+    }
+
+    /// Times this mesh's `record_draw_commands` on the GPU via a dedicated `GpuTimer`, read back
+    /// through `MeshData::last_gpu_time_ms`. `timestamp_period`/`timestamp_valid_bits` come from
+    /// the graphics queue's `vk::PhysicalDeviceProperties::limits`/`query_timestamp_valid_bits`;
+    /// pass `timestamp_valid_bits` as `0` (or skip this call) on a device that doesn't support
+    /// timestamps, and `last_gpu_time_ms` reports `None` instead of a meaningless reading.
+    ///
+    /// The query pool this allocates must be reset before `cmd_begin_render_pass`, since
+    /// `vkCmdResetQueryPool` isn't allowed inside a render pass instance: call
+    /// `MeshData::reset_gpu_timer` first.
+    pub fn with_gpu_timer(mut self, timestamp_period: f32, timestamp_valid_bits: u32) -> Self {
+        self.gpu_timer = Some((timestamp_period, timestamp_valid_bits));
+        self
+    }
+
     pub fn advances_subpass(mut self) -> Self {
         self.advances_subpass = true;
         self
@@ -478,7 +938,35 @@ impl<'m, 'p> MeshDataBuilder<'m, 'p> {
         self
     }
 
+    /// Supplies a `PipelineCache` whose on-disk blob lets the driver skip recompiling shader
+    /// variants it's already seen in a previous run.
+    pub fn with_pipeline_cache(mut self, pipeline_cache: &'p PipelineCache) -> Self {
+        self.pipeline_cache = Some(pipeline_cache);
+        self
+    }
+
+    /// Opts this mesh into `Renderer::reload_shaders`: `vert_name`/`frag_name` are the same names
+    /// passed to `include_shader!` for its initial build (e.g. `"cube.vert"`), re-read from
+    /// `target/shaders/<name>.spv` each time a reload is triggered.
+    pub fn reloadable(mut self, vert_name: &'static str, frag_name: &'static str) -> Self {
+        self.shader_names = Some((vert_name, frag_name));
+        self
+    }
+
     pub fn build(self) -> MeshData {
+        if let Some(view_count) = self.multiview {
+            for attachment in &self.shader_attachments {
+                if let ShaderAttachment::UniformBuffer(UniformBufferType::StereoCamera(_)) =
+                    attachment
+                {
+                    let expected = StereoCameraUBO::VIEW_COUNT;
+                    let msg = "StereoCamera UBO view count doesn't match with_multiview";
+
+                    assert_eq!(view_count, expected, "{msg}: {expected} vs {view_count}");
+                }
+            }
+        }
+
         let (vertex_buffer, vertex_buffer_memory) = create_buffer_of_type(
             &self.device,
             self.device_mem_properties,
@@ -488,16 +976,27 @@ impl<'m, 'p> MeshDataBuilder<'m, 'p> {
             &self.mesh.vertices,
         );
 
-        let (index_buffer, index_buffer_memory) = create_buffer_of_type(
-            &self.device,
-            self.device_mem_properties,
-            self.command_pool,
-            self.graphics_queue,
-            vk::BufferUsageFlags::INDEX_BUFFER,
-            &self.mesh.indices,
-        );
+        let (index_buffer, index_buffer_memory) = match &self.mesh.indices {
+            Indices::U16(idx) => create_buffer_of_type(
+                &self.device,
+                self.device_mem_properties,
+                self.command_pool,
+                self.graphics_queue,
+                vk::BufferUsageFlags::INDEX_BUFFER,
+                idx,
+            ),
+            Indices::U32(idx) => create_buffer_of_type(
+                &self.device,
+                self.device_mem_properties,
+                self.command_pool,
+                self.graphics_queue,
+                vk::BufferUsageFlags::INDEX_BUFFER,
+                idx,
+            ),
+        };
 
         let index_count = to_u32(self.mesh.indices.len());
+        let index_type = self.mesh.indices.vk_index_type();
 
         let vert_shader = ShaderModule::new(&self.device, self.vert_shader_compiled);
         let frag_shader = ShaderModule::new(&self.device, self.frag_shader_compiled);
@@ -528,8 +1027,67 @@ impl<'m, 'p> MeshDataBuilder<'m, 'p> {
             cb(&mut builder);
         }
 
+        let instance_data = self.instances.as_ref().map(|instances| {
+            let capacity = instances.len();
+            let size = (capacity * size_of::<InstanceData>()) as u64;
+
+            // Also usable as a `ComputeAttachment::StorageBuffer(s)` target, so a `ComputePipeline`
+            // can write instance transforms (e.g. GPU-side culling) for `instance_buffer` below to
+            // hand straight to the instanced draw path, without the CPU touching per-object data.
+            let usage = vk::BufferUsageFlags::VERTEX_BUFFER | vk::BufferUsageFlags::STORAGE_BUFFER;
+
+            let (buffers, memories, mappings) = create_host_visible_shader_buffers::<InstanceData>(
+                &self.device,
+                self.device_mem_properties,
+                usage,
+                size,
+                self.per_frame_copies,
+                None,
+                "instance buffer",
+            );
+
+            for mapping in &mappings {
+                unsafe { mapping.copy_from_nonoverlapping(instances.as_ptr(), capacity) };
+            }
+
+            (buffers, memories, mappings, capacity)
+        });
+
+        if instance_data.is_some() {
+            builder
+                .with_instance_binding(to_u32(size_of::<InstanceData>()), instance_vertex_descs());
+        }
+
+        let pipeline_cache_handle =
+            self.pipeline_cache.map_or(vk::PipelineCache::null(), |c| c.inner);
+
+        if let Some(pipeline_cache) = self.pipeline_cache {
+            builder.with_pipeline_cache(pipeline_cache);
+        }
+
         let pipeline = builder.build(&vert_shader, &frag_shader);
 
+        let (instance_buffers, instance_buffer_memories, instance_mappings, instance_capacity) =
+            match instance_data {
+                Some((buffers, memories, mappings, capacity)) => {
+                    (buffers, memories, mappings, capacity)
+                }
+                None => (vec![], vec![], vec![], 0),
+            };
+        let instance_count = to_u32(instance_capacity);
+
+        let gpu_timer = self.gpu_timer.and_then(|(timestamp_period, timestamp_valid_bits)| {
+            (timestamp_valid_bits > 0).then(|| {
+                GpuTimer::new(
+                    &self.device,
+                    timestamp_period,
+                    timestamp_valid_bits,
+                    self.per_frame_copies,
+                    &["draw"],
+                )
+            })
+        });
+
         MeshData {
             device: self.device,
             vertex_buffer,
@@ -537,28 +1095,228 @@ impl<'m, 'p> MeshDataBuilder<'m, 'p> {
             index_buffer,
             index_buffer_memory,
             index_count,
+            index_type,
             pipeline,
             push_consts: self.push_consts,
+            instance_buffers,
+            instance_buffer_memories,
+            instance_mappings,
+            instance_capacity,
+            instance_count,
             uniform_buffer,
             update_data: None,
             advances_subpass: self.advances_subpass,
+            render_pass: self.render_pass,
+            mesh_builder: modify_builder,
+            extra_builder: self.builder,
+            pipeline_cache: pipeline_cache_handle,
+            shader_names: self.shader_names,
+            gpu_timer,
+        }
+    }
+}
+
+impl<'p> ComputePipelineBuilder<'p> {
+    pub fn new(device: &ash::Device, shader_compiled: &'p [u8], per_frame_copies: usize) -> Self {
+        Self {
+            device: device.clone(),
+            shader_compiled,
+            per_frame_copies,
+            attachments: vec![],
+            push_const_range: None,
+            pipeline_cache: vk::PipelineCache::null(),
+        }
+    }
+
+    pub fn with_storage_buffer(mut self, buffer: vk::Buffer, size: u64) -> Self {
+        self.attachments.push(ComputeAttachment::StorageBuffer(buffer, size));
+        self
+    }
+
+    pub fn with_storage_buffers(mut self, buffers: &'p [(vk::Buffer, u64)]) -> Self {
+        self.attachments.push(ComputeAttachment::StorageBuffers(buffers));
+        self
+    }
+
+    pub fn with_storage_image(mut self, texture: &'p Texture) -> Self {
+        self.attachments.push(ComputeAttachment::StorageImage(texture));
+        self
+    }
+
+    pub fn with_storage_images(mut self, textures: &'p [Texture]) -> Self {
+        self.attachments.push(ComputeAttachment::StorageImages(textures));
+        self
+    }
+
+    pub fn with_push_const_range(mut self, range: vk::PushConstantRange) -> Self {
+        self.push_const_range = Some(range);
+        self
+    }
+
+    /// Supplies a `PipelineCache` whose on-disk blob lets the driver skip recompiling shader
+    /// variants it's already seen in a previous run.
+    pub fn with_pipeline_cache(mut self, pipeline_cache: &PipelineCache) -> Self {
+        self.pipeline_cache = pipeline_cache.inner;
+        self
+    }
+
+    pub fn build(self) -> ComputePipeline {
+        let mut bindings = vec![];
+        let mut pool_sizes = vec![];
+
+        for (binding, att) in self.attachments.iter().enumerate() {
+            let binding = to_u32(binding);
+
+            match att {
+                ComputeAttachment::StorageBuffer(..) | ComputeAttachment::StorageBuffers(_) => {
+                    bindings.push(storage_buffer_binding(binding));
+                    pool_sizes.push(storage_buffer_pool_size(self.per_frame_copies));
+                }
+                ComputeAttachment::StorageImage(_) | ComputeAttachment::StorageImages(_) => {
+                    bindings.push(storage_image_binding(binding));
+                    pool_sizes.push(storage_image_pool_size(self.per_frame_copies));
+                }
+            }
+        }
+
+        let desc_set_layout = create_desc_set_layout(&self.device, &bindings);
+        let desc_pool = create_desc_pool(&self.device, &pool_sizes, self.per_frame_copies);
+        let desc_sets =
+            alloc_desc_sets(&self.device, desc_pool, desc_set_layout, self.per_frame_copies);
+
+        fill_compute_desc_set(&self.device, &self.attachments, &desc_sets, self.per_frame_copies);
+
+        let pipeline = Pipeline::new_compute(
+            &self.device,
+            self.push_const_range.as_ref(),
+            desc_set_layout,
+            self.shader_compiled,
+            self.pipeline_cache,
+            None,
+        );
+
+        ComputePipeline {
+            device: self.device,
+            pipeline,
+            desc_set_layout,
+            desc_pool,
+            desc_sets,
+        }
+    }
+}
+
+impl ComputePipeline {
+    pub fn desc_sets(&self) -> &[vk::DescriptorSet] {
+        &self.desc_sets
+    }
+
+    /// Binds this pipeline plus `current_frame`'s descriptor set into `cmd_buffer` and records a
+    /// dispatch, e.g. before the render pass begins so a following draw call can read what this
+    /// pass wrote. Thin wrapper over the standalone `pipeline::dispatch` so callers working with a
+    /// `ComputePipeline` don't need to slice `desc_sets` down to one frame themselves.
+    pub fn dispatch(
+        &self,
+        cmd_buffer: vk::CommandBuffer,
+        current_frame: usize,
+        group_count_x: u32,
+        group_count_y: u32,
+        group_count_z: u32,
+    ) {
+        let desc_sets = &self.desc_sets[current_frame..=current_frame];
+
+        dispatch(
+            &self.device,
+            cmd_buffer,
+            &self.pipeline,
+            desc_sets,
+            group_count_x,
+            group_count_y,
+            group_count_z,
+        );
+    }
+
+    /// Pushes `bytes` at offset 0 of whatever `vk::PushConstantRange` this pipeline was built
+    /// with (see `ComputePipelineBuilder::with_push_const_range`). Thin wrapper so callers don't
+    /// need `self.pipeline.layout`, which `ComputePipeline` otherwise keeps private.
+    pub fn push_constants(&self, cmd_buffer: vk::CommandBuffer, bytes: &[u8]) {
+        unsafe {
+            self.device.cmd_push_constants(
+                cmd_buffer,
+                self.pipeline.layout,
+                vk::ShaderStageFlags::COMPUTE,
+                0,
+                bytes,
+            );
+        }
+    }
+}
+
+impl Drop for ComputePipeline {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.destroy_descriptor_pool(self.desc_pool, None);
+            self.device.destroy_descriptor_set_layout(self.desc_set_layout, None);
         }
     }
 }
 
 impl MeshData {
     pub unsafe fn record_draw_commands(&self, cmd_buffer: vk::CommandBuffer, current_frame: usize) {
+        self.record_draw_commands_batched(cmd_buffer, current_frame, None);
+    }
+
+    /// Resets this mesh's `GpuTimer` (see `MeshDataBuilder::with_gpu_timer`) for `frame`. Must be
+    /// called before `cmd_begin_render_pass` on whichever render pass will record this mesh's
+    /// draw, since `vkCmdResetQueryPool` isn't legal inside a render pass instance. A no-op if
+    /// `with_gpu_timer` wasn't used or the device doesn't support timestamps.
+    pub unsafe fn reset_gpu_timer(&self, cmd_buffer: vk::CommandBuffer, frame: usize) {
+        if let Some(timer) = &self.gpu_timer {
+            timer.reset(cmd_buffer, frame);
+        }
+    }
+
+    /// This mesh's most recent GPU draw time for `frame`, in milliseconds, or `None` if
+    /// `MeshDataBuilder::with_gpu_timer` wasn't used or the device doesn't support timestamps.
+    pub fn last_gpu_time_ms(&self, frame: usize) -> Option<f32> {
+        self.gpu_timer.as_ref().map(|timer| {
+            #[allow(clippy::cast_possible_truncation)]
+            let ms = timer.resolve(frame)[0].1 as f32;
+
+            ms
+        })
+    }
+
+    /// `record_draw_commands`'s variant for a batched recorder like `Frame::record`: skips
+    /// re-binding the pipeline when `last_pipeline` already matches this mesh's, instead of
+    /// re-binding it on every call the way independently looping over `record_draw_commands`
+    /// does. Every `MeshDataBuilder::build` creates its own pipeline, so this only matches when
+    /// the same `MeshData` is drawn twice in a row. Returns this mesh's pipeline handle so the
+    /// caller can track it across calls.
+    pub unsafe fn record_draw_commands_batched(
+        &self,
+        cmd_buffer: vk::CommandBuffer,
+        current_frame: usize,
+        last_pipeline: Option<vk::Pipeline>,
+    ) -> vk::Pipeline {
         if self.advances_subpass {
             self.device.cmd_next_subpass(cmd_buffer, vk::SubpassContents::INLINE);
         }
 
-        self.device.cmd_bind_pipeline(
-            cmd_buffer,
-            vk::PipelineBindPoint::GRAPHICS,
-            self.pipeline.inner,
-        );
+        if last_pipeline != Some(self.pipeline.inner) {
+            self.device.cmd_bind_pipeline(
+                cmd_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.pipeline.inner,
+            );
+        }
+
         self.device.cmd_bind_vertex_buffers(cmd_buffer, 0, &[self.vertex_buffer], &[0]);
-        self.device.cmd_bind_index_buffer(cmd_buffer, self.index_buffer, 0, vk::IndexType::UINT16);
+        self.device.cmd_bind_index_buffer(cmd_buffer, self.index_buffer, 0, self.index_type);
+
+        if self.instance_capacity > 0 {
+            let instance_buffer = self.instance_buffers[current_frame];
+            self.device.cmd_bind_vertex_buffers(cmd_buffer, 1, &[instance_buffer], &[0]);
+        }
 
         if let Some(p) = &self.push_consts {
             let bytes = p.data.as_bytes();
@@ -579,32 +1337,72 @@ impl MeshData {
             );
         }
 
-        self.device.cmd_draw_indexed(cmd_buffer, self.index_count, 1, 0, 0, 0);
+        let instance_count = if self.instance_capacity > 0 { self.instance_count } else { 1 };
+
+        if let Some(timer) = &self.gpu_timer {
+            timer.begin_scope(cmd_buffer, current_frame, "draw");
+        }
+
+        self.device.cmd_draw_indexed(cmd_buffer, self.index_count, instance_count, 0, 0, 0);
+
+        if let Some(timer) = &self.gpu_timer {
+            timer.end_scope(cmd_buffer, current_frame, "draw");
+        }
+
+        self.pipeline.inner
     }
 
-    pub fn copy_to_uniform_mapping(&mut self, current_frame: usize) {
-        let Some(u) = &self.uniform_buffer else {
+    /// Re-fills this frame's instance buffer, so the world can stream its current set of
+    /// per-instance transforms (e.g. visible block positions) through one draw call without
+    /// rebuilding the mesh. `instances` must not exceed the capacity fixed by
+    /// `MeshDataBuilder::with_instances`; any excess is silently dropped.
+    pub fn update_instances(&mut self, instances: &[InstanceData], current_frame: usize) {
+        if self.instance_capacity == 0 {
             return;
-        };
+        }
 
-        let Some(m) = &u.buf_mem else {
-            return;
-        };
+        let count = instances.len().min(self.instance_capacity);
 
         unsafe {
-            m.mappings[current_frame].copy_from_nonoverlapping(m.data.as_ref(), 1);
+            let mapping = self.instance_mappings[current_frame];
+            mapping.copy_from_nonoverlapping(instances.as_ptr(), count);
         }
+
+        self.instance_count = to_u32(count);
     }
 
-    pub fn uniform_buffer_mut(&mut self) -> Option<&mut UniformBufferType> {
-        let Some(u) = &mut self.uniform_buffer else {
+    /// This frame's instance buffer and its byte size, for passing as a
+    /// `ComputeAttachment::StorageBuffer` to a `ComputePipelineBuilder` (e.g. a culling pass that
+    /// writes transforms here instead of `update_instances` being called from the CPU). Returns
+    /// `None` if this mesh was never given instances by `MeshDataBuilder::with_instances`.
+    pub fn instance_buffer(&self, current_frame: usize) -> Option<(vk::Buffer, u64)> {
+        if self.instance_capacity == 0 {
             return None;
-        };
+        }
 
-        let Some(m) = &mut u.buf_mem else {
-            return None;
+        let size = (self.instance_capacity * size_of::<InstanceData>()) as u64;
+
+        Some((self.instance_buffers[current_frame], size))
+    }
+
+    pub fn copy_to_uniform_mapping(&mut self, current_frame: usize) {
+        let Some(u) = &self.uniform_buffer else {
+            return;
         };
 
+        for m in &u.buf_mems {
+            unsafe {
+                m.mappings[current_frame].copy_from_nonoverlapping(m.data.as_ref(), 1);
+            }
+        }
+    }
+
+    /// The `index`-th `ShaderAttachment::UniformBuffer` this mesh was built with (in the order
+    /// passed to `with_uniform_buffer`), or `None` if it has no uniform buffer at that index.
+    pub fn uniform_buffer_mut(&mut self, index: usize) -> Option<&mut UniformBufferType> {
+        let u = self.uniform_buffer.as_mut()?;
+        let m = u.buf_mems.get_mut(index)?;
+
         Some(m.data.as_mut())
     }
 
@@ -621,6 +1419,53 @@ impl MeshData {
             cb(self, camera, win_size, current_frame);
         }
     }
+
+    /// Re-reads this mesh's shaders from `target/shaders/` (recompiling via `glslc` first, if it's
+    /// on `PATH`) and rebuilds `self.pipeline` in place. A no-op for meshes that weren't opted in
+    /// via `MeshDataBuilder::reloadable`. Caller (`Renderer::reload_shaders`) is responsible for
+    /// having already called `device_wait_idle`, since the old pipeline may still be in flight.
+    pub fn reload_shaders_from_disk(&mut self) -> Result<()> {
+        let Some((vert_name, frag_name)) = self.shader_names else {
+            return Ok(());
+        };
+
+        let vert_bytes = recompile_and_read_shader(vert_name)?;
+        let frag_bytes = recompile_and_read_shader(frag_name)?;
+
+        self.reload_shaders(&vert_bytes, &frag_bytes);
+
+        Ok(())
+    }
+
+    fn reload_shaders(&mut self, vert_bytes: &[u8], frag_bytes: &[u8]) {
+        let vert_shader = ShaderModule::new(&self.device, vert_bytes);
+        let frag_shader = ShaderModule::new(&self.device, frag_bytes);
+
+        let push_const_range = self.push_consts.as_ref().map(|p| p.range);
+        let desc_set_layout = self.uniform_buffer.as_ref().map(|u| u.desc_set_layout);
+
+        let mut builder = PipelineBuilder::new(
+            &self.device,
+            self.render_pass,
+            push_const_range.as_ref(),
+            desc_set_layout.as_ref(),
+        );
+
+        (self.mesh_builder)(&mut builder);
+
+        if let Some(cb) = self.extra_builder {
+            cb(&mut builder);
+        }
+
+        if self.instance_capacity > 0 {
+            let stride = to_u32(size_of::<InstanceData>());
+            builder.with_instance_binding(stride, instance_vertex_descs());
+        }
+
+        builder.with_pipeline_cache_handle(self.pipeline_cache);
+
+        self.pipeline = builder.build(&vert_shader, &frag_shader);
+    }
 }
 
 impl Drop for MeshData {
@@ -630,6 +1475,14 @@ impl Drop for MeshData {
             self.device.free_memory(self.index_buffer_memory, None);
             self.device.destroy_buffer(self.vertex_buffer, None);
             self.device.free_memory(self.vertex_buffer_memory, None);
+
+            for buf in &self.instance_buffers {
+                self.device.destroy_buffer(*buf, None);
+            }
+
+            for mem in &self.instance_buffer_memories {
+                self.device.free_memory(*mem, None);
+            }
         }
     }
 }
@@ -650,6 +1503,8 @@ impl ComputeTarget {
         push_const_type: Option<PushConstType>,
         update_data_cb: UpdateCompDataCb,
         per_frame_copies: usize,
+        debug_data: Option<&DebugData>,
+        pipeline_cache: vk::PipelineCache,
     ) -> Self {
         let queue_indices = &phys_device_info.queue_family_indices;
         let phys_device = phys_device_info.phys_device;
@@ -663,7 +1518,7 @@ impl ComputeTarget {
         let mut color = Vec::with_capacity(per_frame_copies);
         let mut depth = Vec::with_capacity(per_frame_copies);
 
-        for _ in 0..per_frame_copies {
+        for i in 0..per_frame_copies {
             let ct = Texture::new_compute(
                 instance,
                 phys_device,
@@ -674,6 +1529,8 @@ impl ComputeTarget {
                 queues.graphics,
                 width,
                 height,
+                debug_data,
+                &format!("compute color {i}"),
             );
 
             let dt = Texture::new_compute(
@@ -686,6 +1543,8 @@ impl ComputeTarget {
                 queues.graphics,
                 width,
                 height,
+                debug_data,
+                &format!("compute depth {i}"),
             );
 
             color.push(ct);
@@ -695,8 +1554,23 @@ impl ComputeTarget {
         let len_sizes = world::MAX_SIZE_X * world::MAX_SIZE_Z;
         let len_spans = world::MAX_SIZE_X * world::MAX_SIZE_Y * world::MAX_SIZE_Z;
 
-        let sizes = ComputeBufferReadOnlyMemory::new(device, device_mem_properties, len_sizes);
-        let spans = ComputeBufferReadOnlyMemory::new(device, device_mem_properties, len_spans);
+        // Written once (or rarely, on a world edit) and read every dispatch thereafter, so
+        // device-local memory plus a one-off staging upload beats a persistently-mapped
+        // host-visible buffer here (see `ComputeBufferBacking::DeviceLocal`).
+        let sizes = ComputeBufferReadOnlyMemory::new_device_local(
+            device,
+            device_mem_properties,
+            primary_command_pool,
+            queues.graphics,
+            len_sizes,
+        );
+        let spans = ComputeBufferReadOnlyMemory::new_device_local(
+            device,
+            device_mem_properties,
+            primary_command_pool,
+            queues.graphics,
+            len_spans,
+        );
 
         let buffers = vec![sizes, spans];
 
@@ -753,12 +1627,43 @@ impl ComputeTarget {
             push_const_range.as_ref(),
             desc_set_layout,
             compiled_shader,
+            pipeline_cache,
+            None,
         );
         let cmd_pool = create_command_pool(device, compute_queue_idx, true);
         let cmd_buffers = alloc_command_buffers(device, cmd_pool, per_frame_copies);
 
-        let comp_finished_fences = create_fences(device, true, per_frame_copies);
-        let comp_finished_sem = create_semaphores(device, per_frame_copies);
+        let comp_finished_fences =
+            create_fences(device, true, per_frame_copies, debug_data, "comp finished fence");
+        let comp_finished_sem =
+            create_semaphores(device, per_frame_copies, debug_data, "comp finished semaphore");
+
+        let timestamp_valid_bits =
+            query_timestamp_valid_bits(instance, phys_device, compute_queue_idx);
+        let supports_timestamps =
+            phys_device_info.properties.limits.timestamp_compute_and_graphics == vk::TRUE
+                && timestamp_valid_bits > 0;
+        let gpu_timer = supports_timestamps.then(|| {
+            GpuTimer::new(
+                device,
+                phys_device_info.timestamp_period,
+                timestamp_valid_bits,
+                per_frame_copies,
+                &["compute"],
+            )
+        });
+
+        // Like `supports_timestamps` above, queried directly rather than trusted from
+        // `PhysDeviceInfo::satisfied_features`, which only ever echoes back
+        // `DeviceRequirements::required_features` and isn't the device's actual capability set.
+        // `create_logical_device` enables `pipeline_statistics_query` on exactly the devices
+        // where this reports it, so the query pool is only created when the feature is on.
+        let supports_stats_query =
+            unsafe { instance.get_physical_device_features(phys_device) }
+                .pipeline_statistics_query
+                == vk::TRUE;
+        let stats_query =
+            supports_stats_query.then(|| PipelineStatsQuery::new(device, per_frame_copies));
 
         Self {
             device: device.clone(),
@@ -783,9 +1688,37 @@ impl ComputeTarget {
             local_size_x,
             local_size_y,
             clear_color: true,
+            pipeline_cache,
+            shader_name: None,
+            gpu_timer,
+            stats_query,
         }
     }
 
+    /// This compute dispatch's most recent invocation count for `frame`, or `None` if the device
+    /// doesn't support `pipeline_statistics_query`.
+    pub fn last_invocation_count(&self, current_frame: usize) -> Option<u64> {
+        self.stats_query.as_ref().map(|q| q.resolve(current_frame))
+    }
+
+    /// This compute dispatch's most recent GPU time for `frame`, in milliseconds, or `None` if
+    /// the device doesn't support compute timestamps.
+    pub fn last_gpu_time_ms(&self, frame: usize) -> Option<f32> {
+        self.gpu_timer.as_ref().map(|timer| {
+            #[allow(clippy::cast_possible_truncation)]
+            let ms = timer.resolve(frame)[0].1 as f32;
+
+            ms
+        })
+    }
+
+    /// Alias for `last_gpu_time_ms`, named for the single `cmd_dispatch` this timer's `"compute"`
+    /// scope already brackets (see `GpuTimer::begin_scope`/`end_scope` in
+    /// `record_compute_commands`).
+    pub fn last_dispatch_ms(&self, frame: usize) -> Option<f32> {
+        self.last_gpu_time_ms(frame)
+    }
+
     pub fn wait(&self, current_frame: usize) {
         let fence = self.comp_finished_fences[current_frame];
 
@@ -825,6 +1758,11 @@ impl ComputeTarget {
                 .begin_command_buffer(cmd_buffer, &begin_info)
                 .check_err("begin compute command buffer");
 
+            if let Some(timer) = &self.gpu_timer {
+                timer.reset(cmd_buffer, current_frame);
+                timer.begin_scope(cmd_buffer, current_frame, "compute");
+            }
+
             self.device.cmd_bind_pipeline(
                 cmd_buffer,
                 vk::PipelineBindPoint::COMPUTE,
@@ -865,10 +1803,22 @@ impl ComputeTarget {
                 &[],
             );
 
+            if let Some(stats) = &self.stats_query {
+                stats.begin(cmd_buffer, current_frame);
+            }
+
             self.device.cmd_dispatch(cmd_buffer, group_count_x, group_count_y, 1);
 
+            if let Some(stats) = &self.stats_query {
+                stats.end(cmd_buffer, current_frame);
+            }
+
             self.release_barrier_for_compute_queue(cmd_buffer, current_frame);
 
+            if let Some(timer) = &self.gpu_timer {
+                timer.end_scope(cmd_buffer, current_frame, "compute");
+            }
+
             self.device.end_command_buffer(cmd_buffer).check_err("end compute command buffer");
         }
     }
@@ -1027,11 +1977,40 @@ impl ComputeTarget {
     }
 
     pub fn copy_to_buffer(&mut self, idx: usize, data: &[u32]) {
-        let mapping = self.buffers[idx].mapping;
+        self.buffers[idx].upload(data);
+    }
 
-        unsafe {
-            mapping.copy_from_nonoverlapping(data.as_ptr(), data.len());
-        }
+    /// Opts this compute target into `Renderer::reload_shaders`, re-reading `shader_name` (the
+    /// same name passed to `include_shader!` for its initial build) from `target/shaders/`.
+    pub fn set_reloadable(&mut self, shader_name: &'static str) {
+        self.shader_name = Some(shader_name);
+    }
+
+    /// Re-reads this target's shader from `target/shaders/` (recompiling via `glslc` first, if
+    /// it's on `PATH`) and rebuilds `self.pipeline` in place. A no-op unless opted in via
+    /// `set_reloadable`. Caller is responsible for having already called `device_wait_idle`.
+    pub fn reload_shader_from_disk(&mut self) -> Result<()> {
+        let Some(name) = self.shader_name else {
+            return Ok(());
+        };
+
+        let bytes = recompile_and_read_shader(name)?;
+        self.reload_shader(&bytes);
+
+        Ok(())
+    }
+
+    fn reload_shader(&mut self, shader_bytes: &[u8]) {
+        let push_const_range = self.push_consts.as_ref().map(|p| p.range);
+
+        self.pipeline = Pipeline::new_compute(
+            &self.device,
+            push_const_range.as_ref(),
+            self.desc_set_layout,
+            shader_bytes,
+            self.pipeline_cache,
+            None,
+        );
     }
 }
 
@@ -1070,7 +2049,7 @@ impl BufferMemory {
         copies: usize,
     ) -> Self {
         let (buffers, memories, mappings) =
-            create_uniform_buffers(device, device_mem_properties, copies);
+            create_uniform_buffers(device, device_mem_properties, copies, None, "uniform buffer");
         let data = Box::new(*ub_type);
         let size = ub_type.get_size() as u64;
 
@@ -1107,8 +2086,15 @@ impl ComputeBufferReadOnlyMemory {
     ) -> Self {
         let size = u64::from(items) * size_of::<u32>() as u64;
         let usage = vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::TRANSFER_DST;
-        let (buffers, memories, mappings) =
-            create_host_visible_shader_buffers(device, device_mem_properties, usage, size, 1);
+        let (buffers, memories, mappings) = create_host_visible_shader_buffers(
+            device,
+            device_mem_properties,
+            usage,
+            size,
+            1,
+            None,
+            "compute read-only buffer",
+        );
 
         let buffer = buffers[0];
         let memory = memories[0];
@@ -1118,12 +2104,99 @@ impl ComputeBufferReadOnlyMemory {
             device: device.clone(),
             buffer,
             memory,
-            mapping,
             size,
+            backing: ComputeBufferBacking::HostMapped(mapping),
+        }
+    }
+
+    /// Like `new`, but backs `items` with device-local memory instead of a persistently-mapped
+    /// host-visible allocation (see `ComputeBufferBacking::DeviceLocal`).
+    fn new_device_local(
+        device: &ash::Device,
+        device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+        command_pool: vk::CommandPool,
+        queue: vk::Queue,
+        items: u32,
+    ) -> Self {
+        let size = u64::from(items) * size_of::<u32>() as u64;
+        let usage = vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::TRANSFER_DST;
+
+        let (buffer, memory) = unsafe {
+            create_buffer(
+                device,
+                device_mem_properties,
+                size,
+                usage,
+                vk::MemoryPropertyFlags::DEVICE_LOCAL,
+            )
+        };
+
+        Self {
+            device: device.clone(),
+            buffer,
+            memory,
+            size,
+            backing: ComputeBufferBacking::DeviceLocal {
+                device_mem_properties: *device_mem_properties,
+                command_pool,
+                queue,
+            },
+        }
+    }
+
+    /// Writes `data` into this buffer, via a plain `memcpy` for `HostMapped` or a one-off
+    /// staging-buffer upload for `DeviceLocal` (see `ComputeBufferBacking`).
+    fn upload(&self, data: &[u32]) {
+        match &self.backing {
+            ComputeBufferBacking::HostMapped(mapping) => unsafe {
+                mapping.copy_from_nonoverlapping(data.as_ptr(), data.len());
+            },
+            ComputeBufferBacking::DeviceLocal { device_mem_properties, command_pool, queue } => {
+                upload_via_staging(
+                    &self.device,
+                    device_mem_properties,
+                    *command_pool,
+                    *queue,
+                    self.buffer,
+                    data,
+                );
+            }
         }
     }
 }
 
+/// Writes `data` into `dst_buffer` (already allocated with `TRANSFER_DST`) via a temporary
+/// host-visible staging buffer, the same immediate-submit-and-copy idiom `create_buffer_of_type`
+/// uses for a brand new buffer's initial contents.
+fn upload_via_staging(
+    device: &ash::Device,
+    device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+    command_pool: vk::CommandPool,
+    queue: vk::Queue,
+    dst_buffer: vk::Buffer,
+    data: &[u32],
+) {
+    let size = size_of_val(data) as u64;
+
+    let (staging_buffer, staging_memory) = unsafe {
+        create_buffer(
+            device,
+            device_mem_properties,
+            size,
+            vk::BufferUsageFlags::TRANSFER_SRC,
+            vk::MemoryPropertyFlags::HOST_VISIBLE,
+        )
+    };
+
+    upload_to_buffer_memory(device, staging_memory, data);
+    copy_buffers(device, command_pool, queue, staging_buffer, dst_buffer, size);
+
+    unsafe {
+        device.destroy_buffer(staging_buffer, None);
+        device.free_memory(staging_memory, None);
+    }
+}
+
 impl Drop for ComputeBufferReadOnlyMemory {
     fn drop(&mut self) {
         unsafe {
@@ -1136,7 +2209,9 @@ impl Drop for ComputeBufferReadOnlyMemory {
 impl UniformBufferType {
     fn get_size(&self) -> usize {
         match self {
-            Self::ModelViewProj(_) => size_of::<ModelViewProjUBO>(),
+            Self::ModelViewProj(_) => ModelViewProjUBO::std140_size(),
+            Self::CameraView(_) => CameraViewUBO::std140_size(),
+            Self::StereoCamera(_) => StereoCameraUBO::std140_size(),
         }
     }
 }
@@ -1163,7 +2238,7 @@ impl PushConstType {
     }
 }
 
-const fn create_push_const_range(
+pub(super) const fn create_push_const_range(
     size: u32,
     stage_flags: vk::ShaderStageFlags,
 ) -> vk::PushConstantRange {
@@ -1186,7 +2261,11 @@ fn build_uniform_buffer(
 
     let mut bindings = vec![];
     let mut pool_sizes = vec![];
-    let mut buf_mem = None;
+    let mut buf_mems = vec![];
+    // One entry per `bindings`, so `create_desc_set_layout_update_after_bind` can chain them in as
+    // `VkDescriptorSetLayoutBindingFlagsCreateInfo`; stays all-`empty()` unless a `TextureArray`
+    // binding is present, in which case the whole layout needs the update-after-bind path.
+    let mut binding_flags = vec![];
 
     for (binding, att) in shader_attachments.iter().enumerate() {
         let binding = to_u32(binding);
@@ -1195,33 +2274,58 @@ fn build_uniform_buffer(
             ShaderAttachment::UniformBuffer(u) => {
                 bindings.push(uniform_binding(binding));
                 pool_sizes.push(uniform_pool_size(copies));
+                binding_flags.push(vk::DescriptorBindingFlags::empty());
 
-                assert!(buf_mem.is_none(), "multiple uniform buffers are not supported");
-                buf_mem = Some(BufferMemory::new(device, device_mem_properties, u, copies));
+                buf_mems.push(BufferMemory::new(device, device_mem_properties, u, copies));
             }
             ShaderAttachment::Texture(_) | ShaderAttachment::Textures(_) => {
                 bindings.push(sampler_binding(binding));
                 pool_sizes.push(sampler_pool_size(copies));
+                binding_flags.push(vk::DescriptorBindingFlags::empty());
             }
             ShaderAttachment::InputAttachment(_) => {
                 bindings.push(input_att_binding(binding));
                 pool_sizes.push(input_attachment_pool_size(copies));
+                binding_flags.push(vk::DescriptorBindingFlags::empty());
+            }
+            ShaderAttachment::AccelerationStructure(_) => {
+                bindings.push(acceleration_structure_binding(binding));
+                pool_sizes.push(acceleration_structure_pool_size(copies));
+                binding_flags.push(vk::DescriptorBindingFlags::empty());
+            }
+            ShaderAttachment::TextureArray(ts) => {
+                bindings.push(texture_array_binding(binding, to_u32(ts.len())));
+                pool_sizes.push(sampler_pool_size(copies * ts.len()));
+                binding_flags.push(
+                    vk::DescriptorBindingFlags::PARTIALLY_BOUND
+                        | vk::DescriptorBindingFlags::UPDATE_AFTER_BIND,
+                );
             }
         }
     }
 
-    let desc_set_layout = create_desc_set_layout(device, &bindings);
-    let desc_pool = create_desc_pool(device, &pool_sizes, copies);
+    let needs_update_after_bind = binding_flags.iter().any(|f| !f.is_empty());
+
+    let desc_set_layout = if needs_update_after_bind {
+        create_desc_set_layout_update_after_bind(device, &bindings, &binding_flags)
+    } else {
+        create_desc_set_layout(device, &bindings)
+    };
+    let desc_pool = if needs_update_after_bind {
+        create_desc_pool_update_after_bind(device, &pool_sizes, copies)
+    } else {
+        create_desc_pool(device, &pool_sizes, copies)
+    };
     let desc_sets = alloc_desc_sets(device, desc_pool, desc_set_layout, copies);
 
-    fill_desc_set(device, shader_attachments, &desc_sets, &buf_mem, copies);
+    fill_desc_set(device, shader_attachments, &desc_sets, &buf_mems, copies);
 
     let uniform_buffer = UniformBuffer {
         device: device.clone(),
         desc_set_layout,
         desc_pool,
         desc_sets,
-        buf_mem,
+        buf_mems,
     };
 
     Some(uniform_buffer)
@@ -1243,6 +2347,24 @@ const fn input_att_binding(binding: u32) -> vk::DescriptorSetLayoutBinding {
     desc_binding(binding, vk::DescriptorType::INPUT_ATTACHMENT, vk::ShaderStageFlags::FRAGMENT)
 }
 
+const fn acceleration_structure_binding(binding: u32) -> vk::DescriptorSetLayoutBinding {
+    desc_binding(
+        binding,
+        vk::DescriptorType::ACCELERATION_STRUCTURE_KHR,
+        vk::ShaderStageFlags::FRAGMENT,
+    )
+}
+
+const fn texture_array_binding(binding: u32, count: u32) -> vk::DescriptorSetLayoutBinding {
+    vk::DescriptorSetLayoutBinding {
+        binding,
+        descriptor_type: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+        descriptor_count: count,
+        stage_flags: vk::ShaderStageFlags::FRAGMENT,
+        p_immutable_samplers: std::ptr::null(),
+    }
+}
+
 const fn storage_image_binding(binding: u32) -> vk::DescriptorSetLayoutBinding {
     desc_binding(binding, vk::DescriptorType::STORAGE_IMAGE, vk::ShaderStageFlags::COMPUTE)
 }
@@ -1286,6 +2408,13 @@ const fn input_attachment_pool_size(count: usize) -> vk::DescriptorPoolSize {
     }
 }
 
+const fn acceleration_structure_pool_size(count: usize) -> vk::DescriptorPoolSize {
+    vk::DescriptorPoolSize {
+        ty: vk::DescriptorType::ACCELERATION_STRUCTURE_KHR,
+        descriptor_count: to_u32(count),
+    }
+}
+
 const fn storage_image_pool_size(count: usize) -> vk::DescriptorPoolSize {
     vk::DescriptorPoolSize {
         ty: vk::DescriptorType::STORAGE_IMAGE,
@@ -1314,6 +2443,33 @@ fn create_desc_set_layout(
         .check_err("create descriptor set layout")
 }
 
+/// Like `create_desc_set_layout`, but for a layout with at least one `TextureArray` binding:
+/// chains `binding_flags` (one entry per `bindings`) in as a
+/// `VkDescriptorSetLayoutBindingFlagsCreateInfo` and sets `UPDATE_AFTER_BIND_POOL`, both required
+/// before any binding's `PARTIALLY_BOUND`/`UPDATE_AFTER_BIND` flags are legal.
+fn create_desc_set_layout_update_after_bind(
+    device: &ash::Device,
+    bindings: &[vk::DescriptorSetLayoutBinding],
+    binding_flags: &[vk::DescriptorBindingFlags],
+) -> vk::DescriptorSetLayout {
+    let flags_info = vk::DescriptorSetLayoutBindingFlagsCreateInfo {
+        binding_count: to_u32(binding_flags.len()),
+        p_binding_flags: binding_flags.as_ptr(),
+        ..Default::default()
+    };
+
+    let create_info = vk::DescriptorSetLayoutCreateInfo {
+        binding_count: to_u32(bindings.len()),
+        p_bindings: bindings.as_ptr(),
+        flags: vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL,
+        p_next: std::ptr::from_ref(&flags_info).cast::<std::ffi::c_void>().cast_mut(),
+        ..Default::default()
+    };
+
+    unsafe { device.create_descriptor_set_layout(&create_info, None) }
+        .check_err("create descriptor set layout")
+}
+
 fn create_desc_pool(
     device: &ash::Device,
     pool_sizes: &[vk::DescriptorPoolSize],
@@ -1329,6 +2485,24 @@ fn create_desc_pool(
     unsafe { device.create_descriptor_pool(&create_info, None) }.check_err("create descriptor pool")
 }
 
+/// Like `create_desc_pool`, but with `UPDATE_AFTER_BIND`, the pool-side counterpart of
+/// `create_desc_set_layout_update_after_bind`'s `UPDATE_AFTER_BIND_POOL` layout flag.
+fn create_desc_pool_update_after_bind(
+    device: &ash::Device,
+    pool_sizes: &[vk::DescriptorPoolSize],
+    max_sets: usize,
+) -> vk::DescriptorPool {
+    let create_info = vk::DescriptorPoolCreateInfo {
+        max_sets: to_u32(max_sets),
+        pool_size_count: to_u32(pool_sizes.len()),
+        p_pool_sizes: pool_sizes.as_ptr(),
+        flags: vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND,
+        ..Default::default()
+    };
+
+    unsafe { device.create_descriptor_pool(&create_info, None) }.check_err("create descriptor pool")
+}
+
 fn alloc_desc_sets(
     device: &ash::Device,
     descriptor_pool: vk::DescriptorPool,
@@ -1351,21 +2525,29 @@ fn fill_desc_set(
     device: &ash::Device,
     shader_attachments: &[ShaderAttachment],
     desc_sets: &[vk::DescriptorSet],
-    buf_mem: &Option<BufferMemory>,
+    buf_mems: &[BufferMemory],
     copies: usize,
 ) {
     #[allow(clippy::needless_range_loop)]
     for frame in 0..copies {
-        let mut buf_infos = vec![];
-        let mut img_infos = vec![];
+        // `store` hands back a reference into these vecs that gets coerced to a raw pointer and
+        // stashed in a `WriteDescriptorSet` below, so none of them may reallocate once the loop
+        // below starts pushing into them: reserve each one's true worst case (every attachment
+        // landing in that vec) up front.
+        let mut buf_infos = Vec::with_capacity(shader_attachments.len());
+        let mut img_infos = Vec::with_capacity(shader_attachments.len());
+        let mut as_handles = Vec::with_capacity(shader_attachments.len());
+        let mut as_infos = Vec::with_capacity(shader_attachments.len());
+        let mut array_img_infos = Vec::with_capacity(shader_attachments.len());
         let mut desc_writes = vec![];
+        let mut next_buf_mem = buf_mems.iter();
 
         for (binding, att) in shader_attachments.iter().enumerate() {
             let binding = to_u32(binding);
 
             match att {
                 ShaderAttachment::UniformBuffer(_) => {
-                    let buf_mem = buf_mem.as_ref().unwrap_or_else(|| unreachable!());
+                    let buf_mem = next_buf_mem.next().unwrap_or_else(|| unreachable!());
                     let buf_info = buffer_desc_info(buf_mem.buffers[frame], buf_mem.size);
                     let buf_info = store(&mut buf_infos, buf_info);
                     let buf_write = buffer_desc_write(desc_sets[frame], binding, buf_info);
@@ -1394,6 +2576,73 @@ fn fill_desc_set(
 
                     desc_writes.push(att_write);
                 }
+                ShaderAttachment::AccelerationStructure(accel) => {
+                    let handle = store(&mut as_handles, accel.inner);
+                    let as_info = acceleration_structure_desc_info(handle);
+                    let as_info = store(&mut as_infos, as_info);
+                    let as_write =
+                        acceleration_structure_desc_write(desc_sets[frame], binding, as_info);
+
+                    desc_writes.push(as_write);
+                }
+                ShaderAttachment::TextureArray(ts) => {
+                    let infos: Vec<vk::DescriptorImageInfo> =
+                        ts.iter().map(sampler_desc_info).collect();
+                    let infos = store(&mut array_img_infos, infos);
+                    let array_write = texture_array_desc_write(desc_sets[frame], binding, infos);
+
+                    desc_writes.push(array_write);
+                }
+            }
+        }
+
+        unsafe { device.update_descriptor_sets(&desc_writes, &[]) };
+    }
+}
+
+fn fill_compute_desc_set(
+    device: &ash::Device,
+    attachments: &[ComputeAttachment],
+    desc_sets: &[vk::DescriptorSet],
+    copies: usize,
+) {
+    #[allow(clippy::needless_range_loop)]
+    for frame in 0..copies {
+        // Same reallocate-out-from-under-a-stashed-pointer hazard as `fill_desc_set`: reserve the
+        // true worst case up front so no push can ever move these vecs' backing storage.
+        let mut buf_infos = Vec::with_capacity(attachments.len());
+        let mut img_infos = Vec::with_capacity(attachments.len());
+        let mut desc_writes = vec![];
+
+        for (binding, att) in attachments.iter().enumerate() {
+            let binding = to_u32(binding);
+
+            match att {
+                ComputeAttachment::StorageBuffer(buffer, size) => {
+                    let buf_info = buffer_desc_info(*buffer, *size);
+                    let buf_info = store(&mut buf_infos, buf_info);
+
+                    desc_writes.push(ssbo_desc_write(desc_sets[frame], binding, buf_info));
+                }
+                ComputeAttachment::StorageBuffers(buffers) => {
+                    let (buffer, size) = buffers[frame];
+                    let buf_info = buffer_desc_info(buffer, size);
+                    let buf_info = store(&mut buf_infos, buf_info);
+
+                    desc_writes.push(ssbo_desc_write(desc_sets[frame], binding, buf_info));
+                }
+                ComputeAttachment::StorageImage(t) => {
+                    let img_info = sampler_desc_info(t);
+                    let img_info = store(&mut img_infos, img_info);
+
+                    desc_writes.push(storage_img_desc_write(desc_sets[frame], binding, img_info));
+                }
+                ComputeAttachment::StorageImages(ts) => {
+                    let img_info = sampler_desc_info(&ts[frame]);
+                    let img_info = store(&mut img_infos, img_info);
+
+                    desc_writes.push(storage_img_desc_write(desc_sets[frame], binding, img_info));
+                }
             }
         }
 
@@ -1414,6 +2663,202 @@ fn buffer_desc_info(buffer: vk::Buffer, range: u64) -> vk::DescriptorBufferInfo
     }
 }
 
+/// One flat normal per triangle (`mesh.indices` in groups of 3, guaranteed by `triangulate: true`)
+/// for `Mesh::from_obj` files that don't specify their own normals.
+fn flat_face_normals(mesh: &tobj::Mesh) -> Vec<[f32; 3]> {
+    let position = |i: u32| {
+        let o = i as usize * 3;
+        vec3(mesh.positions[o], mesh.positions[o + 1], mesh.positions[o + 2])
+    };
+
+    mesh.indices
+        .chunks_exact(3)
+        .map(|tri| {
+            let (a, b, c) = (position(tri[0]), position(tri[1]), position(tri[2]));
+            let normal = (b - a).cross(c - a).normalize_or_zero();
+
+            [normal.x, normal.y, normal.z]
+        })
+        .collect()
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn to_u16(len: usize) -> u16 {
+    len as u16
+}
+
+const IQM_MAGIC: &[u8; 16] = b"INTERQUAKEMODEL\0";
+const IQM_VERSION: u32 = 2;
+
+repr_enum! {
+    /// The `iqmvertexarray::type` values this loader looks for; any other value found in the file
+    /// (blend indices/weights, tangents, per-vertex color, ...) is skipped over untouched.
+    enum IqmVertexArrayType: u32 {
+        0 => Position,
+        1 => TexCoord,
+        2 => Normal,
+    }
+}
+
+const IQM_FLOAT: u32 = 7;
+
+/// Size in bytes of one `iqmmesh` entry (6 `uint`s: name, material, first/num vertex, first/num
+/// triangle) and one `iqmtriangle` entry (3 `uint` vertex indices).
+const IQM_MESH_SIZE: usize = 6 * 4;
+const IQM_TRIANGLE_SIZE: usize = 3 * 4;
+const IQM_VERTEXARRAY_SIZE: usize = 5 * 4;
+
+/// The handful of `iqmheader` fields this loader actually needs; the rest of the header (joints,
+/// poses, anims, frames, comments, extensions) is skipped over since nothing here consumes it.
+struct IqmHeader {
+    num_meshes: u32,
+    ofs_meshes: u32,
+    num_vertexarrays: u32,
+    num_vertexes: u32,
+    ofs_vertexarrays: u32,
+    ofs_triangles: u32,
+    num_text: u32,
+    ofs_text: u32,
+}
+
+impl IqmHeader {
+    fn parse(bytes: &[u8]) -> Result<Self> {
+        ensure!(bytes.len() >= 16, "IQM file is too short to hold a header");
+        ensure!(&bytes[..16] == IQM_MAGIC, "not an IQM file (bad magic)");
+
+        let version = iqm_u32(bytes, 16)?;
+        ensure!(version == IQM_VERSION, "unsupported IQM version {version}, expected 2");
+
+        Ok(Self {
+            num_text: iqm_u32(bytes, 28)?,
+            ofs_text: iqm_u32(bytes, 32)?,
+            num_meshes: iqm_u32(bytes, 36)?,
+            ofs_meshes: iqm_u32(bytes, 40)?,
+            num_vertexarrays: iqm_u32(bytes, 44)?,
+            num_vertexes: iqm_u32(bytes, 48)?,
+            ofs_vertexarrays: iqm_u32(bytes, 52)?,
+            ofs_triangles: iqm_u32(bytes, 60)?,
+        })
+    }
+}
+
+fn iqm_u32(bytes: &[u8], offset: usize) -> Result<u32> {
+    let slice = bytes
+        .get(offset..offset + 4)
+        .ok_or_else(|| anyhow!("IQM file truncated at offset {offset}"))?;
+
+    Ok(u32::from_le_bytes(slice.try_into().unwrap_or_else(|_| unreachable!())))
+}
+
+fn iqm_f32(bytes: &[u8], offset: usize) -> Result<f32> {
+    Ok(f32::from_bits(iqm_u32(bytes, offset)?))
+}
+
+/// Reads the null-terminated string starting at `header.ofs_text + offset` out of the text blob
+/// (`iqmmesh::material`, `iqmmesh::name`, etc. are all offsets into it).
+fn iqm_text(bytes: &[u8], header: &IqmHeader, offset: u32) -> Result<String> {
+    let start = header.ofs_text as usize + offset as usize;
+    let end_of_blob = header.ofs_text as usize + header.num_text as usize;
+
+    ensure!(start <= end_of_blob, "IQM text offset {offset} is outside the text blob");
+
+    let blob = bytes
+        .get(start..end_of_blob)
+        .ok_or_else(|| anyhow!("IQM text blob runs past end of file"))?;
+    let len = blob.iter().position(|&b| b == 0).unwrap_or(blob.len());
+
+    Ok(String::from_utf8_lossy(&blob[..len]).into_owned())
+}
+
+/// Reads `count` floats starting at `start` out of an already-decoded vertex array, erroring
+/// instead of panicking if a mesh entry's vertex range runs past what the array actually holds.
+fn iqm_slice(data: &[f32], start: usize, count: usize) -> Result<&[f32]> {
+    data.get(start..start + count)
+        .ok_or_else(|| anyhow!("IQM vertex index {} is out of range of its vertex array", start))
+}
+
+/// Finds the `iqmvertexarray` entry matching `array_type` and reads its data as `components`-wide
+/// `f32` tuples, one per vertex in the file. Only the `FLOAT` vertex array format is supported,
+/// matching every IQM exporter in practice.
+fn iqm_vertex_array(
+    bytes: &[u8],
+    header: &IqmHeader,
+    array_type: IqmVertexArrayType,
+    components: usize,
+) -> Result<Option<Vec<f32>>> {
+    for i in 0..header.num_vertexarrays as usize {
+        let entry_off = header.ofs_vertexarrays as usize + i * IQM_VERTEXARRAY_SIZE;
+
+        if IqmVertexArrayType::from_repr(iqm_u32(bytes, entry_off)?) != Ok(array_type) {
+            continue;
+        }
+
+        let format = iqm_u32(bytes, entry_off + 8)?;
+        ensure!(format == IQM_FLOAT, "IQM vertex array type {array_type:?} isn't FLOAT-formatted");
+
+        let size = iqm_u32(bytes, entry_off + 12)? as usize;
+        ensure!(size == components, "IQM vertex array type {array_type:?} has {size} components");
+
+        let offset = iqm_u32(bytes, entry_off + 16)? as usize;
+        let floats = header.num_vertexes as usize * components;
+
+        let mut data = Vec::with_capacity(floats);
+
+        for f in 0..floats {
+            data.push(iqm_f32(bytes, offset + f * 4)?);
+        }
+
+        return Ok(Some(data));
+    }
+
+    Ok(None)
+}
+
+/// Invokes `glslc --target-env=vulkan1.2 -o target/shaders/<name>.spv shaders/<name>` if `glslc`
+/// is on `PATH` and the source file exists, then reads the resulting SPIR-V. `name` is the same
+/// string passed to `include_shader!`, so its source lives at `shaders/<name>` and its compiled
+/// output at `target/shaders/<name>.spv` either way.
+fn recompile_and_read_shader(name: &str) -> Result<Vec<u8>> {
+    let source_path = format!("shaders/{name}");
+    let spv_path = format!("target/shaders/{name}.spv");
+
+    if Path::new(&source_path).exists() {
+        let status = Command::new("glslc")
+            .args(["--target-env=vulkan1.2", "-o", &spv_path, &source_path])
+            .status();
+
+        if let Ok(status) = status {
+            ensure!(status.success(), "glslc failed to compile {source_path}");
+        }
+    }
+
+    Ok(std::fs::read(&spv_path)?)
+}
+
+/// Per-instance attribute layout matching `InstanceData`: a `Mat4` as four `location`s (one per
+/// column, since `vk::Format` has no 4x4 type) followed by a `Vec3` tint, all on binding 1.
+fn instance_vertex_descs() -> Vec<vk::VertexInputAttributeDescription> {
+    let mat4_col = |location: u32, offset: u32| vk::VertexInputAttributeDescription {
+        location,
+        binding: 1,
+        format: vk::Format::R32G32B32A32_SFLOAT,
+        offset,
+    };
+
+    vec![
+        mat4_col(2, 0),
+        mat4_col(3, 4 * SIZE_F32),
+        mat4_col(4, 8 * SIZE_F32),
+        mat4_col(5, 12 * SIZE_F32),
+        vk::VertexInputAttributeDescription {
+            location: 6,
+            binding: 1,
+            format: vk::Format::R32G32B32_SFLOAT,
+            offset: 16 * SIZE_F32,
+        },
+    ]
+}
+
 fn sampler_desc_info(texture: &Texture) -> vk::DescriptorImageInfo {
     vk::DescriptorImageInfo {
         sampler: texture.sampler,
@@ -1430,6 +2875,16 @@ fn input_att_desc_info(attachment: &FramebufferAttachment) -> vk::DescriptorImag
     }
 }
 
+fn acceleration_structure_desc_info(
+    as_handle: &vk::AccelerationStructureKHR,
+) -> vk::WriteDescriptorSetAccelerationStructureKHR {
+    vk::WriteDescriptorSetAccelerationStructureKHR {
+        acceleration_structure_count: 1,
+        p_acceleration_structures: as_handle,
+        ..Default::default()
+    }
+}
+
 fn buffer_desc_write(
     dst_set: vk::DescriptorSet,
     dst_binding: u32,
@@ -1494,6 +2949,42 @@ fn input_att_desc_write(
     }
 }
 
+/// Chains `as_info` into `p_next`, the acceleration-structure-equivalent of `p_buffer_info`/
+/// `p_image_info`: `VkWriteDescriptorSet` has no dedicated field for this descriptor type.
+fn acceleration_structure_desc_write(
+    dst_set: vk::DescriptorSet,
+    dst_binding: u32,
+    as_info: &vk::WriteDescriptorSetAccelerationStructureKHR,
+) -> vk::WriteDescriptorSet {
+    vk::WriteDescriptorSet {
+        dst_set,
+        dst_binding,
+        dst_array_element: 0,
+        descriptor_count: 1,
+        descriptor_type: vk::DescriptorType::ACCELERATION_STRUCTURE_KHR,
+        p_next: std::ptr::from_ref(as_info).cast::<std::ffi::c_void>().cast_mut(),
+        ..Default::default()
+    }
+}
+
+/// Unlike every other `*_desc_write` here, `descriptor_count` isn't 1: one write covers the whole
+/// `TextureArray` binding, `image_infos` long.
+fn texture_array_desc_write(
+    dst_set: vk::DescriptorSet,
+    dst_binding: u32,
+    image_infos: &[vk::DescriptorImageInfo],
+) -> vk::WriteDescriptorSet {
+    vk::WriteDescriptorSet {
+        dst_set,
+        dst_binding,
+        dst_array_element: 0,
+        descriptor_count: to_u32(image_infos.len()),
+        descriptor_type: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+        p_image_info: image_infos.as_ptr(),
+        ..Default::default()
+    }
+}
+
 fn storage_img_desc_write(
     dst_set: vk::DescriptorSet,
     dst_binding: u32,
@@ -1,18 +1,34 @@
+mod allocator;
+mod frame;
+mod gpu_timer;
 mod mesh;
 mod pipeline;
+mod particles;
+mod pipeline_cache;
+mod post_pass;
+mod ray_trace;
 mod render_pass;
+mod upload_pool;
 mod vulkan;
 
 use std::default::Default;
 use std::mem::{size_of, ManuallyDrop};
 
 use anyhow::Result;
-use ash::extensions::ext::DebugUtils;
+use ash::extensions::ext::{DebugReport, DebugUtils};
 use ash::vk;
-use glam::{vec2, vec3, Mat4, Vec2, Vec3};
+use glam::{vec2, vec3, Mat4, Vec2, Vec3, Vec4};
 
+use self::allocator::*;
+use self::frame::*;
+use self::gpu_timer::*;
 use self::mesh::*;
+use self::particles::*;
+use self::pipeline_cache::*;
+use self::post_pass::*;
+use self::ray_trace::*;
 use self::render_pass::*;
+use self::upload_pool::*;
 use self::vulkan::*;
 use crate::camera::Camera;
 use crate::image::Image;
@@ -32,6 +48,10 @@ pub const SIZE_F32: u32 = to_u32(size_of::<f32>());
 
 pub const DRAW_TIMEOUT_NS: u64 = 5 * 1000 * 1000 * 1000;
 
+/// Fixed simulation timestep for `ParticleSystem::step`, matching `main_loop::DT`'s 60Hz update
+/// rate without introducing a dependency on that module from this one.
+const PARTICLE_SIM_DT: f32 = 1.0 / 60.0;
+
 pub struct Renderer {
     instance: ash::Instance,
     debug_data: Option<DebugData>,
@@ -50,23 +70,56 @@ pub struct Renderer {
     attachments: Vec<FramebufferAttachment>,
     render_pass: vk::RenderPass,
     framebuffers: Vec<vk::Framebuffer>,
-    image_available: Vec<vk::Semaphore>,
+    current_acquire_semaphore: vk::Semaphore,
     render_finished: Vec<vk::Semaphore>,
     is_rendering: Vec<vk::Fence>,
+    pipeline_cache: ManuallyDrop<PipelineCache>,
     texture: ManuallyDrop<Texture>,
-    compute_target: Option<ComputeTarget>,
-    compute_target_mesh: Option<MeshData>,
+    compute_targets: Vec<ComputeTarget>,
+    compute_target_meshes: Vec<MeshData>,
+    post_passes: Vec<PostPass>,
+    particle_systems: Vec<ParticleSystem>,
+    particle_view_proj: Mat4,
     meshes: Vec<MeshData>,
     current_frame: usize,
     per_frame_copies: usize,
     win_width: u32,
     win_height: u32,
     win_resized: bool,
+    /// Set by `enable_frame_capture`; makes `record_commands` copy every frame's swapchain image
+    /// into `readback_buffer` for `read_back_frame` to hand back, at the cost of one extra
+    /// image-to-buffer copy per frame that nobody pays otherwise.
+    capture_frames: bool,
+    readback_buffer: vk::Buffer,
+    readback_memory: vk::DeviceMemory,
+    /// Extent `readback_buffer` was sized for; `record_commands` recreates it if the swapchain's
+    /// extent (e.g. after a window resize) no longer matches.
+    readback_extent: vk::Extent2D,
+    /// A copy of the just-rendered swapchain image, taken every frame in `record_commands` right
+    /// after the main scene pass ends; this is what `add_post_pass` binds as `Original` and as the
+    /// first pass's `Source`, instead of the static demo texture.
+    scene_capture: Texture,
+    /// Extent `scene_capture` was sized for; recreated on mismatch the same way as
+    /// `readback_extent`/`readback_buffer`.
+    scene_capture_extent: vk::Extent2D,
+    /// Whether `cmd_blit_image` is supported between the swapchain format and itself, cached once
+    /// at construction since `formats_support_blit` is a per-format-pair physical-device query, not
+    /// something that changes frame-to-frame or across a resize. Used to composite the post-
+    /// processing chain's final output back onto the swapchain image before presenting it.
+    composite_supports_blit: bool,
 }
 
-pub struct DebugData {
-    debug_utils_loader: DebugUtils,
-    debug_messenger: vk::DebugUtilsMessengerEXT,
+/// `VK_EXT_debug_utils` is required where available; `VK_EXT_debug_report` is a fallback for
+/// older drivers and portability stacks that only expose the legacy extension.
+pub enum DebugData {
+    Utils {
+        debug_utils_loader: DebugUtils,
+        debug_messenger: vk::DebugUtilsMessengerEXT,
+    },
+    Report {
+        debug_report_loader: DebugReport,
+        debug_report_callback: vk::DebugReportCallbackEXT,
+    },
 }
 
 #[repr(C)]
@@ -77,6 +130,54 @@ pub struct ModelViewProjUBO {
     proj: Mat4,
 }
 
+impl AsStd140 for ModelViewProjUBO {
+    fn std140_size() -> usize {
+        size_of::<Self>()
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CameraViewUBO {
+    view: Mat4,
+    proj: Mat4,
+    world_position: Std140Vec3,
+}
+
+impl AsStd140 for CameraViewUBO {
+    fn std140_size() -> usize {
+        size_of::<Self>()
+    }
+}
+
+/// One eye's view/projection pair.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ViewProj {
+    pub view: Mat4,
+    pub proj: Mat4,
+}
+
+/// Both eyes' view/projection pairs, indexed in the vertex shader by `gl_ViewIndex` when the
+/// subpass this mesh draws into was built with `SubpassBuilder::with_view_mask`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct StereoCameraUBO {
+    pub eyes: [ViewProj; 2],
+}
+
+impl StereoCameraUBO {
+    /// How many views' worth of matrices `eyes` holds, for `MeshDataBuilder::with_multiview` to
+    /// check against the view count the render pass's subpass was built with.
+    pub const VIEW_COUNT: u32 = 2;
+}
+
+impl AsStd140 for StereoCameraUBO {
+    fn std140_size() -> usize {
+        size_of::<Self>()
+    }
+}
+
 #[repr(C, packed)]
 #[derive(Default)]
 pub struct RayCastPushConstants {
@@ -112,6 +213,22 @@ pub struct RayTracePushConstants {
     _pad: f32,
 }
 
+/// One instance's world transform and tint for GPU-instanced drawing (see
+/// `MeshDataBuilder::with_instances`), read at `location = 2..=6` of the per-instance vertex
+/// binding alongside the mesh's own per-vertex attributes at binding 0.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct InstanceData {
+    pub modelmatrix: Mat4,
+    pub colour: Vec3,
+}
+
+impl InstanceData {
+    pub const fn new(modelmatrix: Mat4, colour: Vec3) -> Self {
+        Self { modelmatrix, colour }
+    }
+}
+
 impl Renderer {
     #[allow(clippy::too_many_lines)]
     pub fn new(app_name: &'static str, window: &Window) -> Result<Self> {
@@ -119,7 +236,8 @@ impl Renderer {
         let instance = create_instance(app_name, &entry, window);
         let debug_data = create_debug_data(&entry, &instance);
         let surface = ManuallyDrop::new(Surface::new(&entry, &instance, window)?);
-        let phys_device_info = pick_phys_device(&instance, &surface);
+        let device_requirements = DeviceRequirements::default_for_game();
+        let phys_device_info = pick_phys_device(&instance, &surface, &device_requirements);
         let phys_device = phys_device_info.phys_device;
         let device_mem_properties =
             unsafe { instance.get_physical_device_memory_properties(phys_device) };
@@ -130,8 +248,13 @@ impl Renderer {
         let swapchain_format = choose_swapchain_format(phys_device, &surface);
         let depth_format = find_depth_format(&instance, phys_device);
 
-        let render_pass =
-            create_render_pass_no_attachments(&device, swapchain_format.format, depth_format);
+        let render_pass = create_render_pass_no_attachments(
+            &device,
+            swapchain_format.format,
+            depth_format,
+            None,
+            None,
+        );
 
         let (swapchain, depth_textures, attachments, framebuffers, viewport, scissor) =
             Self::create_swapchain_and_accessories(
@@ -145,6 +268,7 @@ impl Renderer {
                 &device_mem_properties,
                 depth_format,
                 render_pass,
+                debug_data.as_ref(),
             );
 
         let graphics_queue_idx = phys_device_info.queue_family_indices.graphics;
@@ -152,18 +276,47 @@ impl Renderer {
         let command_pool = create_command_pool(&device, graphics_queue_idx, true);
         let command_buffers = alloc_command_buffers(&device, command_pool, per_frame_copies);
 
-        let image_available = create_semaphores(&device, per_frame_copies);
-        let render_finished = create_semaphores(&device, per_frame_copies);
-        let is_rendering = create_fences(&device, true, per_frame_copies);
+        let render_finished =
+            create_semaphores(&device, per_frame_copies, debug_data.as_ref(), "render finished");
+        let is_rendering =
+            create_fences(&device, true, per_frame_copies, debug_data.as_ref(), "is rendering");
+
+        let pipeline_cache = ManuallyDrop::new(PipelineCache::new(
+            &device,
+            &phys_device_info.properties,
+            "pipeline_cache.bin",
+        ));
 
         let texture = ManuallyDrop::new(Texture::new(
+            &instance,
+            phys_device,
             &device,
             &device_mem_properties,
             command_pool,
             queues.graphics,
             "assets/cat.jxl",
+            debug_data.as_ref(),
+            "cat",
         ));
 
+        let scene_capture = Texture::new_capture(
+            &device,
+            &device_mem_properties,
+            command_pool,
+            queues.graphics,
+            swapchain_format.format,
+            swapchain.extent.width,
+            swapchain.extent.height,
+            debug_data.as_ref(),
+            "scene capture",
+        );
+        let composite_supports_blit = formats_support_blit(
+            &instance,
+            phys_device,
+            swapchain_format.format,
+            swapchain_format.format,
+        );
+
         let meshes = create_meshes(
             window,
             &device,
@@ -175,9 +328,12 @@ impl Renderer {
             &attachments,
             &depth_textures,
             per_frame_copies,
+            &pipeline_cache,
         );
 
-        let inst = Self {
+        let scene_capture_extent = swapchain.extent;
+
+        let mut inst = Self {
             instance,
             debug_data,
             surface,
@@ -195,23 +351,46 @@ impl Renderer {
             depth_textures,
             attachments,
             framebuffers,
-            image_available,
+            current_acquire_semaphore: vk::Semaphore::null(),
             render_finished,
             is_rendering,
+            pipeline_cache,
             texture,
-            compute_target: None,
-            compute_target_mesh: None,
+            compute_targets: vec![],
+            compute_target_meshes: vec![],
+            post_passes: vec![],
+            particle_systems: vec![],
+            particle_view_proj: Mat4::IDENTITY,
             meshes,
             current_frame: 0,
             per_frame_copies,
             win_width,
             win_height,
             win_resized: false,
+            capture_frames: false,
+            readback_buffer: vk::Buffer::null(),
+            readback_memory: vk::DeviceMemory::null(),
+            readback_extent: vk::Extent2D::default(),
+            scene_capture_extent,
+            scene_capture,
+            composite_supports_blit,
         };
 
+        // A single passthrough stage so the post-processing chain built in `record_commands` has
+        // at least one real pass running every frame instead of always being empty.
+        inst.add_post_pass(
+            include_shader!("textured_screen_quad.frag"),
+            Scale::Relative(1.0),
+            FilterMode::Linear,
+            &[PostPassInput::Source],
+        );
+
+        inst.add_particle_system();
+
         Ok(inst)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn create_swapchain_and_accessories(
         phys_device_info: &PhysDeviceInfo,
         surface: &Surface,
@@ -223,6 +402,7 @@ impl Renderer {
         device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
         depth_format: vk::Format,
         render_pass: vk::RenderPass,
+        debug_data: Option<&DebugData>,
     ) -> (
         Swapchain,
         Vec<FramebufferAttachment>,
@@ -240,21 +420,52 @@ impl Renderer {
             instance,
             device,
             &phys_device_info.queue_family_indices,
+            debug_data,
         );
 
+        let (depth_textures, attachments, framebuffers, viewport, scissor) =
+            Self::create_swapchain_accessories(
+                &swapchain,
+                device,
+                device_mem_properties,
+                depth_format,
+                render_pass,
+                win_width,
+                win_height,
+                debug_data,
+            );
+
+        (swapchain, depth_textures, attachments, framebuffers, viewport, scissor)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_swapchain_accessories(
+        swapchain: &Swapchain,
+        device: &ash::Device,
+        device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+        depth_format: vk::Format,
+        render_pass: vk::RenderPass,
+        win_width: u32,
+        win_height: u32,
+        debug_data: Option<&DebugData>,
+    ) -> (Vec<FramebufferAttachment>, Vec<FramebufferAttachment>, Vec<vk::Framebuffer>, vk::Viewport, vk::Rect2D)
+    {
         let num_swapchain_images = swapchain.image_views.len();
 
         let mut depth_textures = Vec::with_capacity(num_swapchain_images);
 
-        for _ in 0..num_swapchain_images {
+        for i in 0..num_swapchain_images {
             let depth_texture = FramebufferAttachment::new(
                 device,
                 device_mem_properties,
                 swapchain.extent,
                 depth_format,
+                1,
                 vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT
                     | vk::ImageUsageFlags::INPUT_ATTACHMENT,
                 vk::ImageAspectFlags::DEPTH,
+                debug_data,
+                &format!("depth {i}"),
             );
 
             depth_textures.push(depth_texture);
@@ -285,11 +496,11 @@ impl Renderer {
             extent: swapchain.extent,
         };
 
-        (swapchain, depth_textures, attachments, framebuffers, viewport, scissor)
+        (depth_textures, attachments, framebuffers, viewport, scissor)
     }
 
     pub fn draw(&mut self) {
-        if let Some(ct) = &self.compute_target {
+        for ct in &self.compute_targets {
             ct.wait(self.current_frame);
             ct.record_compute_commands(self.current_frame);
             ct.submit(self.current_frame);
@@ -309,6 +520,125 @@ impl Renderer {
         self.current_frame %= FRAMES_IN_FLIGHT as usize;
     }
 
+    /// Starts copying every rendered frame's swapchain image into a host-visible staging buffer,
+    /// for `read_back_frame` to hand back to `MainLoop::benchmark`'s `--record` flag. Only call
+    /// this in benchmark mode: unlike everything else `record_commands` draws, this copy happens
+    /// every frame whether or not anyone ever reads the result.
+    pub fn enable_frame_capture(&mut self) {
+        self.capture_frames = true;
+    }
+
+    /// (Re)creates `readback_buffer` sized for the swapchain's current extent, if it hasn't
+    /// already been created at that size. Called from `record_commands`, so a window resize mid-
+    /// benchmark picks up the new size on the next captured frame instead of reading back stale
+    /// or undersized data.
+    fn ensure_readback_buffer(&mut self) {
+        let already_sized = self.readback_buffer != vk::Buffer::null()
+            && self.readback_extent == self.swapchain.extent;
+
+        if already_sized {
+            return;
+        }
+
+        unsafe {
+            if self.readback_buffer != vk::Buffer::null() {
+                self.device.destroy_buffer(self.readback_buffer, None);
+                self.device.free_memory(self.readback_memory, None);
+            }
+
+            let extent = self.swapchain.extent;
+            let size = u64::from(extent.width) * u64::from(extent.height) * 4;
+
+            let (buffer, memory) = create_buffer(
+                &self.device,
+                &self.device_mem_properties,
+                size,
+                vk::BufferUsageFlags::TRANSFER_DST,
+                vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+            );
+
+            self.readback_buffer = buffer;
+            self.readback_memory = memory;
+            self.readback_extent = extent;
+        }
+    }
+
+    /// (Re)creates `scene_capture` sized for the swapchain's current extent, if it hasn't already
+    /// been created at that size. Called every frame from `record_commands`, so a window resize
+    /// picks up the new size before the next frame's capture copy instead of leaving a
+    /// wrong-sized target for `cmd_copy_image` to reject.
+    fn ensure_scene_capture(&mut self) {
+        if self.scene_capture_extent == self.swapchain.extent {
+            return;
+        }
+
+        let extent = self.swapchain.extent;
+
+        self.scene_capture = Texture::new_capture(
+            &self.device,
+            &self.device_mem_properties,
+            self.command_pool,
+            self.queues.graphics,
+            self.swapchain.format.format,
+            extent.width,
+            extent.height,
+            self.debug_data.as_ref(),
+            "scene capture",
+        );
+        self.scene_capture_extent = extent;
+    }
+
+    /// The last captured frame's framebuffer as tightly-packed `width * height * 3` RGB bytes, for
+    /// `MainLoop::benchmark`'s `--record` flag. `None` until `enable_frame_capture` has been
+    /// called and at least one frame has been drawn since.
+    pub fn read_back_frame(&self) -> Option<Vec<u8>> {
+        if !self.capture_frames || self.readback_buffer == vk::Buffer::null() {
+            return None;
+        }
+
+        // `self.current_frame` was already advanced past the frame `draw()` just submitted, so
+        // the frame whose copy we're about to read back is one slot behind it.
+        let last_frame = (self.current_frame + self.per_frame_copies - 1) % self.per_frame_copies;
+
+        unsafe {
+            self.device
+                .wait_for_fences(&[self.is_rendering[last_frame]], true, DRAW_TIMEOUT_NS)
+                .check_err("wait for fences");
+        }
+
+        let extent = self.readback_extent;
+        let pixel_count = extent.width as usize * extent.height as usize;
+        let bgra_order = matches!(
+            self.swapchain.format.format,
+            vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB
+        );
+
+        let mut rgba = vec![0u8; pixel_count * 4];
+
+        unsafe {
+            let mapped = self
+                .device
+                .map_memory(self.readback_memory, 0, rgba.len() as u64, vk::MemoryMapFlags::empty())
+                .check_err("map readback memory");
+
+            std::ptr::copy_nonoverlapping(mapped.cast::<u8>(), rgba.as_mut_ptr(), rgba.len());
+
+            self.device.unmap_memory(self.readback_memory);
+        }
+
+        let mut rgb = Vec::with_capacity(pixel_count * 3);
+
+        for px in rgba.chunks_exact(4) {
+            if bgra_order {
+                rgb.extend_from_slice(&[px[2], px[1], px[0]]);
+            } else {
+                rgb.extend_from_slice(&[px[0], px[1], px[2]]);
+            }
+        }
+
+        Some(rgb)
+    }
+
     fn wait(&self) {
         let is_rendering = self.is_rendering[self.current_frame];
 
@@ -320,37 +650,32 @@ impl Renderer {
     }
 
     fn acquire_image(&mut self) -> Option<u32> {
-        let image_available = self.image_available[self.current_frame];
         let is_rendering = self.is_rendering[self.current_frame];
 
-        unsafe {
-            let res = self.swapchain.loader.acquire_next_image(
-                self.swapchain.handle,
-                DRAW_TIMEOUT_NS,
-                image_available,
-                vk::Fence::null(),
-            );
+        match self.swapchain.acquire_next_image() {
+            AcquireResult::Image { index, semaphore } => {
+                self.current_acquire_semaphore = semaphore;
 
-            match res {
-                // If the swapchain is suboptimal, wait until `present_frame()` to recreate it,
-                // in case the number of images will change on resize.
-                Ok((image_index, _suboptimal)) => {
+                unsafe {
                     self.device.reset_fences(&[is_rendering]).check_err("reset fences");
-                    Some(image_index)
                 }
-                Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
-                    self.recreate_swapchain();
-                    None
-                }
-                Err(e) => panic!("failed to acquire next image: err = {}", e),
+
+                Some(index)
+            }
+            // If the swapchain is suboptimal, wait until `present_frame()` to recreate it, in
+            // case the number of images will change on resize.
+            AcquireResult::NeedsRecreation => {
+                self.recreate_swapchain();
+                None
             }
         }
     }
 
-    fn record_commands(&self, image_index: u32) {
+    fn record_commands(&mut self, image_index: u32) {
         let framebuffer = self.framebuffers[image_index as usize];
         let cmd_buffer = self.command_buffers[self.current_frame];
         let begin_info = ONE_TIME_SUBMIT;
+        let particle_view_proj = self.particle_view_proj;
 
         let clear_values = [CLEAR_COLOR, CLEAR_DEPTH];
 
@@ -372,10 +697,22 @@ impl Renderer {
                 .begin_command_buffer(cmd_buffer, &begin_info)
                 .check_err("begin recording to command buffer");
 
-            if let Some(ct) = &self.compute_target {
+            for ct in &self.compute_targets {
                 ct.acquire_barrier_for_graphics_queue(cmd_buffer, self.current_frame);
             }
 
+            for ps in &mut self.particle_systems {
+                let push_consts = ParticleSimPushConstants {
+                    gravity: Vec4::new(0.0, -9.8, 0.0, 0.0),
+                    attractor: Vec4::ZERO,
+                    dt: PARTICLE_SIM_DT,
+                    particle_count: ps.capacity(),
+                };
+
+                ps.step(cmd_buffer, &push_consts);
+                ps.barrier_before_draw(cmd_buffer);
+            }
+
             self.device.cmd_set_viewport(cmd_buffer, 0, &[self.viewport]);
 
             self.device.cmd_set_scissor(cmd_buffer, 0, &[self.scissor]);
@@ -386,7 +723,7 @@ impl Renderer {
                 vk::SubpassContents::INLINE,
             );
 
-            if let Some(cm) = &self.compute_target_mesh {
+            for cm in &self.compute_target_meshes {
                 cm.record_draw_commands(cmd_buffer, self.current_frame);
             }
 
@@ -394,33 +731,154 @@ impl Renderer {
                 mesh.record_draw_commands(cmd_buffer, self.current_frame);
             }
 
+            for ps in &self.particle_systems {
+                ps.record_draw(cmd_buffer, particle_view_proj);
+            }
+
             self.device.cmd_end_render_pass(cmd_buffer);
 
+            self.ensure_scene_capture();
+
+            let swapchain_image = self.swapchain.images[image_index as usize];
+            let swapchain_extent = self.swapchain.extent;
+
+            record_image_layout_transition(
+                &self.device,
+                cmd_buffer,
+                vk::QUEUE_FAMILY_IGNORED,
+                vk::QUEUE_FAMILY_IGNORED,
+                swapchain_image,
+                BASE_SUBRESOURCE_RANGE,
+                vk::ImageLayout::PRESENT_SRC_KHR,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            );
+
+            record_image_layout_transition(
+                &self.device,
+                cmd_buffer,
+                vk::QUEUE_FAMILY_IGNORED,
+                vk::QUEUE_FAMILY_IGNORED,
+                self.scene_capture.image,
+                BASE_SUBRESOURCE_RANGE,
+                self.scene_capture.layout,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            );
+
+            record_copy_image_to_image(
+                &self.device,
+                cmd_buffer,
+                swapchain_image,
+                self.scene_capture.image,
+                swapchain_extent,
+            );
+
+            record_image_layout_transition(
+                &self.device,
+                cmd_buffer,
+                vk::QUEUE_FAMILY_IGNORED,
+                vk::QUEUE_FAMILY_IGNORED,
+                self.scene_capture.image,
+                BASE_SUBRESOURCE_RANGE,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                self.scene_capture.layout,
+            );
+
+            record_image_layout_transition(
+                &self.device,
+                cmd_buffer,
+                vk::QUEUE_FAMILY_IGNORED,
+                vk::QUEUE_FAMILY_IGNORED,
+                swapchain_image,
+                BASE_SUBRESOURCE_RANGE,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                vk::ImageLayout::PRESENT_SRC_KHR,
+            );
+
+            for pass in &self.post_passes {
+                pass.record(cmd_buffer, self.current_frame);
+            }
+
+            if let Some(last_pass) = self.post_passes.last() {
+                let final_texture = &last_pass.textures()[self.current_frame];
+
+                record_present_blit(
+                    &self.device,
+                    cmd_buffer,
+                    final_texture.image,
+                    final_texture.layout,
+                    last_pass.extent(),
+                    swapchain_image,
+                    swapchain_extent,
+                    self.composite_supports_blit,
+                    false,
+                );
+
+                record_image_layout_transition(
+                    &self.device,
+                    cmd_buffer,
+                    vk::QUEUE_FAMILY_IGNORED,
+                    vk::QUEUE_FAMILY_IGNORED,
+                    swapchain_image,
+                    BASE_SUBRESOURCE_RANGE,
+                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                    vk::ImageLayout::PRESENT_SRC_KHR,
+                );
+            }
+
+            if self.capture_frames {
+                self.ensure_readback_buffer();
+
+                record_image_layout_transition(
+                    &self.device,
+                    cmd_buffer,
+                    vk::QUEUE_FAMILY_IGNORED,
+                    vk::QUEUE_FAMILY_IGNORED,
+                    swapchain_image,
+                    BASE_SUBRESOURCE_RANGE,
+                    vk::ImageLayout::PRESENT_SRC_KHR,
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                );
+
+                record_copy_image_to_buffer(
+                    &self.device,
+                    cmd_buffer,
+                    swapchain_image,
+                    self.readback_buffer,
+                    swapchain_extent,
+                );
+
+                record_image_layout_transition(
+                    &self.device,
+                    cmd_buffer,
+                    vk::QUEUE_FAMILY_IGNORED,
+                    vk::QUEUE_FAMILY_IGNORED,
+                    swapchain_image,
+                    BASE_SUBRESOURCE_RANGE,
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    vk::ImageLayout::PRESENT_SRC_KHR,
+                );
+            }
+
             self.device.end_command_buffer(cmd_buffer).check_err("end command buffer recording");
         }
     }
 
     fn submit(&mut self) {
         let cmd_buffer = &self.command_buffers[self.current_frame];
-        let image_available = self.image_available[self.current_frame];
+        let image_available = self.current_acquire_semaphore;
         let render_finished = self.render_finished[self.current_frame];
         let is_rendering = self.is_rendering[self.current_frame];
 
-        let wait_semaphores;
-        let wait_dst_stages;
+        let mut wait_semaphores = vec![];
+        let mut wait_dst_stages = vec![];
 
-        if let Some(ct) = &self.compute_target {
-            let compute_finished = ct.compute_finished(self.current_frame);
+        for ct in &self.compute_targets {
+            wait_semaphores.push(ct.compute_finished(self.current_frame));
+            wait_dst_stages.push(vk::PipelineStageFlags::COMPUTE_SHADER);
+        }
 
-            wait_semaphores = vec![compute_finished, image_available];
-            wait_dst_stages = vec![
-                vk::PipelineStageFlags::COMPUTE_SHADER,
-                vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
-            ];
-        } else {
-            wait_semaphores = vec![image_available];
-            wait_dst_stages = vec![vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
-        };
+        wait_semaphores.push(image_available);
+        wait_dst_stages.push(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT);
 
         let submit_info = vk::SubmitInfo {
             wait_semaphore_count: to_u32(wait_semaphores.len()),
@@ -456,13 +914,15 @@ impl Renderer {
     pub fn update_data(&mut self, camera: &mut Camera, world: &mut World) {
         let win_size = (to_f32(self.win_width), to_f32(self.win_height));
 
-        if let Some(ct) = &mut self.compute_target {
+        for ct in &mut self.compute_targets {
             ct.update_data(camera, world, self.current_frame);
         }
 
         for mesh in &mut self.meshes {
             mesh.update_data(camera, win_size, self.current_frame);
         }
+
+        self.particle_view_proj = *camera.proj() * *camera.view();
     }
 
     pub fn handle_resize(&mut self, w: u32, h: u32) {
@@ -502,7 +962,7 @@ impl Renderer {
                 }
             };
 
-        let compute_target = ComputeTarget::new(
+        let mut compute_target = ComputeTarget::new(
             &self.instance,
             &self.phys_device_info,
             &self.device,
@@ -517,8 +977,12 @@ impl Renderer {
             Some(PushConstType::RayCast(compute_push_consts)),
             compute_update_data_cb,
             self.per_frame_copies,
+            self.debug_data.as_ref(),
+            self.pipeline_cache.inner,
         );
 
+        compute_target.set_reloadable("raycasting.comp");
+
         let compute_textures = compute_target.textures();
 
         let mesh = Mesh::textured_screen_quad()
@@ -533,33 +997,133 @@ impl Renderer {
                 include_shader!("textured_screen_quad.frag"),
             )
             .with_textures(compute_textures)
+            .with_pipeline_cache(&self.pipeline_cache)
             .build();
 
-        self.compute_target = Some(compute_target);
-        self.compute_target_mesh = Some(mesh);
+        self.compute_targets.push(compute_target);
+        self.compute_target_meshes.push(mesh);
+    }
+
+    /// Appends one stage to the post-processing chain, sized and filtered per `scale`/`filter`,
+    /// reading whichever of `inputs` it asks for. See `PostPass`'s doc comment for what `Source`
+    /// and `Original` actually resolve to today.
+    pub fn add_post_pass(
+        &mut self,
+        frag_shader_compiled: &[u8],
+        scale: Scale,
+        filter: FilterMode,
+        inputs: &[PostPassInput],
+    ) {
+        let source = match self.post_passes.last() {
+            Some(prev) => PostPassSource::PerFrame(prev.textures()),
+            None => PostPassSource::Static(&self.scene_capture),
+        };
+        let original = PostPassSource::Static(&self.scene_capture);
+
+        let source_extent = self.swapchain.extent;
+        let format = self.swapchain.format.format;
+
+        let post_pass = PostPass::new(
+            &self.device,
+            &self.device_mem_properties,
+            self.command_pool,
+            self.queues.graphics,
+            format,
+            source_extent,
+            scale,
+            filter,
+            inputs,
+            frag_shader_compiled,
+            source,
+            original,
+            self.per_frame_copies,
+            &self.pipeline_cache,
+            self.debug_data.as_ref(),
+        );
+
+        self.post_passes.push(post_pass);
+    }
+
+    /// Adds a small GPU particle system (compute-simulated, drawn straight from its storage
+    /// buffer) into the main scene pass, so `ParticleSystem`'s dispatch-then-draw pipeline
+    /// actually runs every frame instead of sitting unused. Constant downward gravity, no point
+    /// attractor.
+    pub fn add_particle_system(&mut self) {
+        let capacity = 4096;
+        let local_size_x = 256;
+
+        let particle_system = ParticleSystem::new(
+            &self.device,
+            &self.device_mem_properties,
+            self.command_pool,
+            self.queues.graphics,
+            include_shader!("particles.comp"),
+            include_shader!("particles.vert"),
+            include_shader!("particles.frag"),
+            self.render_pass,
+            0,
+            capacity,
+            local_size_x,
+            self.pipeline_cache.inner,
+        );
+
+        self.particle_systems.push(particle_system);
+    }
+
+    /// Rebuilds every mesh's and compute target's pipeline from whatever's currently in
+    /// `target/shaders/`, without tearing down the swapchain, so editing a `.vert`/`.frag`/`.comp`
+    /// source and calling this is enough for an edit-save-see loop. Only meshes/targets opted in
+    /// via `MeshDataBuilder::reloadable`/`ComputeTarget::set_reloadable` are affected; everything
+    /// else keeps the pipeline it was originally built with.
+    pub fn reload_shaders(&mut self) -> Result<()> {
+        unsafe {
+            self.device.device_wait_idle().check_err("wait for device");
+        }
+
+        for mesh in self.meshes.iter_mut().chain(self.compute_target_meshes.iter_mut()) {
+            mesh.reload_shaders_from_disk()?;
+        }
+
+        for compute_target in &mut self.compute_targets {
+            compute_target.reload_shader_from_disk()?;
+        }
+
+        Ok(())
     }
 
     fn recreate_swapchain(&mut self) {
         unsafe {
             self.device.device_wait_idle().check_err("wait for device");
-            self.cleanup_swapchain();
+
+            for fb in &self.framebuffers {
+                self.device.destroy_framebuffer(*fb, None);
+            }
         }
 
-        let (swapchain, depth_textures, attachments, framebuffers, viewport, scissor) =
-            Self::create_swapchain_and_accessories(
-                &self.phys_device_info,
-                &self.surface,
-                self.swapchain.format,
-                self.win_width,
-                self.win_height,
-                &self.instance,
+        self.depth_textures.drain(..);
+        self.attachments.drain(..);
+
+        self.swapchain.recreate(
+            self.phys_device_info.phys_device,
+            &self.surface,
+            self.win_width,
+            self.win_height,
+            &self.phys_device_info.queue_family_indices,
+            self.debug_data.as_ref(),
+        );
+
+        let (depth_textures, attachments, framebuffers, viewport, scissor) =
+            Self::create_swapchain_accessories(
+                &self.swapchain,
                 &self.device,
                 &self.device_mem_properties,
                 self.depth_format,
                 self.render_pass,
+                self.win_width,
+                self.win_height,
+                self.debug_data.as_ref(),
             );
 
-        self.swapchain = swapchain;
         self.depth_textures = depth_textures;
         self.attachments = attachments;
         self.framebuffers = framebuffers;
@@ -584,10 +1148,6 @@ impl Drop for Renderer {
         unsafe {
             self.device.device_wait_idle().check_err("wait for device");
 
-            for sem in &self.image_available {
-                self.device.destroy_semaphore(*sem, None);
-            }
-
             for sem in &self.render_finished {
                 self.device.destroy_semaphore(*sem, None);
             }
@@ -598,15 +1158,26 @@ impl Drop for Renderer {
 
             self.cleanup_swapchain();
 
+            if self.readback_buffer != vk::Buffer::null() {
+                self.device.destroy_buffer(self.readback_buffer, None);
+                self.device.free_memory(self.readback_memory, None);
+            }
+
             ManuallyDrop::drop(&mut self.texture);
 
-            self.compute_target.take();
-            self.compute_target_mesh.take();
+            self.compute_targets.drain(..);
+            self.compute_target_meshes.drain(..);
+
+            self.post_passes.drain(..);
+
+            self.particle_systems.drain(..);
 
             self.device.destroy_render_pass(self.render_pass, None);
 
             self.meshes.drain(..);
 
+            ManuallyDrop::drop(&mut self.pipeline_cache);
+
             self.device.destroy_command_pool(self.command_pool, None);
             self.device.destroy_device(None);
             ManuallyDrop::drop(&mut self.surface);
@@ -621,7 +1192,14 @@ impl Drop for Renderer {
 impl Drop for DebugData {
     fn drop(&mut self) {
         unsafe {
-            self.debug_utils_loader.destroy_debug_utils_messenger(self.debug_messenger, None);
+            match self {
+                DebugData::Utils { debug_utils_loader, debug_messenger } => {
+                    debug_utils_loader.destroy_debug_utils_messenger(*debug_messenger, None);
+                }
+                DebugData::Report { debug_report_loader, debug_report_callback } => {
+                    debug_report_loader.destroy_debug_report_callback(*debug_report_callback, None);
+                }
+            }
         }
     }
 }
@@ -638,14 +1216,17 @@ fn create_attachments(
     let usage = vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::INPUT_ATTACHMENT;
     let aspect_mask = vk::ImageAspectFlags::COLOR;
 
-    for _ in &swapchain.image_views {
+    for (i, _) in swapchain.image_views.iter().enumerate() {
         let attachment = FramebufferAttachment::new(
             device,
             device_mem_properties,
             extent,
             format,
+            1,
             usage,
             aspect_mask,
+            None,
+            &format!("color {i}"),
         );
 
         attachments.push(attachment);
@@ -654,6 +1235,13 @@ fn create_attachments(
     attachments
 }
 
+/// The skybox draws at the far plane of an infinite-depth projection, so it needs
+/// `LESS_OR_EQUAL` depth comparison (plain `LESS` would cull it against its own far-plane
+/// fragments) and no depth writes or culling, since it's a single inside-out screen-filling quad.
+fn skybox_pipeline_modifier(builder: &mut PipelineBuilder) -> &mut PipelineBuilder {
+    builder.with_skybox_depth()
+}
+
 #[allow(unused_variables, clippy::too_many_lines)]
 fn create_meshes(
     window: &Window,
@@ -666,6 +1254,7 @@ fn create_meshes(
     attachments: &[FramebufferAttachment],
     depth_textures: &[FramebufferAttachment],
     per_frame_copies: usize,
+    pipeline_cache: &PipelineCache,
 ) -> Vec<MeshData> {
     let win_sx = to_f32(window.width());
     let win_sy = to_f32(window.height());
@@ -695,6 +1284,8 @@ fn create_meshes(
                 PushConstType::Skybox(skybox_push_consts),
                 vk::ShaderStageFlags::FRAGMENT,
             )
+            .with_pipeline_cache(pipeline_cache)
+            .modify_builder(skybox_pipeline_modifier)
             .build();
 
         skybox.set_update_data_cb(|mesh, camera, win_size, _current_frame| {
@@ -731,6 +1322,7 @@ fn create_meshes(
                 PushConstType::Crosshair(crosshair_push_consts),
                 vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
             )
+            .with_pipeline_cache(pipeline_cache)
             .build();
 
         crosshair.set_update_data_cb(|mesh, _camera, win_size, _current_frame| {
@@ -762,10 +1354,11 @@ fn create_meshes(
                 include_shader!("grid.frag"),
             )
             .with_uniform_buffer(UniformBufferType::ModelViewProj(mvp))
+            .with_pipeline_cache(pipeline_cache)
             .build();
 
         grid.set_update_data_cb(|mesh, camera, _win_size, current_frame| {
-            if let Some(UniformBufferType::ModelViewProj(m)) = mesh.uniform_buffer_mut() {
+            if let Some(UniformBufferType::ModelViewProj(m)) = mesh.uniform_buffer_mut(0) {
                 m.view = *camera.view();
                 m.proj = *camera.proj();
             }
@@ -795,10 +1388,11 @@ fn create_meshes(
                 include_shader!("cube.frag"),
             )
             .with_uniform_buffer(UniformBufferType::ModelViewProj(mvp))
+            .with_pipeline_cache(pipeline_cache)
             .build();
 
         cube_lines.set_update_data_cb(|mesh, camera, _win_size, current_frame| {
-            if let Some(UniformBufferType::ModelViewProj(m)) = mesh.uniform_buffer_mut() {
+            if let Some(UniformBufferType::ModelViewProj(m)) = mesh.uniform_buffer_mut(0) {
                 m.view = *camera.view();
                 m.proj = *camera.proj();
             }
@@ -828,10 +1422,11 @@ fn create_meshes(
                 include_shader!("colored.frag"),
             )
             .with_uniform_buffer(UniformBufferType::ModelViewProj(mvp))
+            .with_pipeline_cache(pipeline_cache)
             .build();
 
         axes.set_update_data_cb(|mesh, camera, _win_size, current_frame| {
-            if let Some(UniformBufferType::ModelViewProj(m)) = mesh.uniform_buffer_mut() {
+            if let Some(UniformBufferType::ModelViewProj(m)) = mesh.uniform_buffer_mut(0) {
                 m.view = *camera.view();
                 m.proj = *camera.proj();
             }
@@ -862,10 +1457,11 @@ fn create_meshes(
             )
             .with_uniform_buffer(UniformBufferType::ModelViewProj(mvp))
             .with_texture(texture)
+            .with_pipeline_cache(pipeline_cache)
             .build();
 
         quad.set_update_data_cb(|mesh, camera, _win_size, current_frame| {
-            if let Some(UniformBufferType::ModelViewProj(m)) = mesh.uniform_buffer_mut() {
+            if let Some(UniformBufferType::ModelViewProj(m)) = mesh.uniform_buffer_mut(0) {
                 m.view = *camera.view();
                 m.proj = *camera.proj();
             }
@@ -895,10 +1491,11 @@ fn create_meshes(
                 include_shader!("colored.frag"),
             )
             .with_uniform_buffer(UniformBufferType::ModelViewProj(mvp))
+            .with_pipeline_cache(pipeline_cache)
             .build();
 
         cube.set_update_data_cb(|mesh, camera, _win_size, current_frame| {
-            if let Some(UniformBufferType::ModelViewProj(m)) = mesh.uniform_buffer_mut() {
+            if let Some(UniformBufferType::ModelViewProj(m)) = mesh.uniform_buffer_mut(0) {
                 m.view = *camera.view();
                 m.proj = *camera.proj();
             }
@@ -909,5 +1506,60 @@ fn create_meshes(
         cube
     };
 
-    vec![]
+    // The "many cubes" stress case: one `Mesh`, one `MeshData`, one `cmd_draw_indexed` call with
+    // `instanceCount = instances.len()`, instead of a separate `MeshData` + UBO per cube like
+    // `cube` above. Positions are fixed at build time here; a caller that needs to move instances
+    // around frame-to-frame would call `update_instances` from an `update_data` callback the same
+    // way the UBO-backed meshes above call `copy_to_uniform_mapping`.
+    let instanced_cubes = {
+        let grid_radius = 10_i32;
+        let spacing = 2.0;
+
+        let instances = (-grid_radius..grid_radius)
+            .flat_map(|x| (-grid_radius..grid_radius).map(move |z| (x, z)))
+            .map(|(x, z)| InstanceData {
+                modelmatrix: Mat4::from_translation(vec3(
+                    i32_to_f32(x) * spacing,
+                    4.0,
+                    i32_to_f32(z) * spacing,
+                )),
+                colour: vec3(0.6, 0.6, 0.6),
+            })
+            .collect();
+
+        let mvp = ModelViewProjUBO {
+            model: Mat4::IDENTITY,
+            view: Mat4::IDENTITY,
+            proj: Mat4::IDENTITY,
+        };
+
+        let mut instanced_cubes = Mesh::cube(0.5)
+            .to_builder(
+                device,
+                device_mem_properties,
+                command_pool,
+                graphics_queue,
+                render_pass,
+                per_frame_copies,
+                include_shader!("colored.vert"),
+                include_shader!("colored.frag"),
+            )
+            .with_uniform_buffer(UniformBufferType::ModelViewProj(mvp))
+            .with_instances(instances)
+            .with_pipeline_cache(pipeline_cache)
+            .build();
+
+        instanced_cubes.set_update_data_cb(|mesh, camera, _win_size, current_frame| {
+            if let Some(UniformBufferType::ModelViewProj(m)) = mesh.uniform_buffer_mut(0) {
+                m.view = *camera.view();
+                m.proj = *camera.proj();
+            }
+
+            mesh.copy_to_uniform_mapping(current_frame);
+        });
+
+        instanced_cubes
+    };
+
+    vec![skybox, crosshair, grid, cube_lines, axes, quad, cube, instanced_cubes]
 }
@@ -0,0 +1,258 @@
+use ash::vk;
+use glam::{Mat4, Vec4};
+
+use super::pipeline::*;
+use super::vulkan::*;
+use super::*;
+use crate::utils::*;
+
+/// One particle's GPU-resident state, laid out to match a std430 storage buffer (natively
+/// aligned `vec4`s), not an `AsStd140` uniform buffer like `ModelViewProjUBO`: `ParticleSystem`
+/// only ever reads/writes this from a compute shader and a vertex shader, never from the CPU.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ParticleGpu {
+    pub pos: Vec4,
+    pub vel: Vec4,
+}
+
+impl ParticleGpu {
+    /// A particle with `pos.w <= 0.0`, the "dead, needs respawning by the emitter" sentinel the
+    /// compute shader's `pos.w` (lifetime remaining) convention relies on.
+    pub const fn dead() -> Self {
+        Self { pos: Vec4::ZERO, vel: Vec4::ZERO }
+    }
+}
+
+/// Per-dispatch knobs for the simulation compute shader, pushed fresh every `step` call: gravity
+/// and an optional point attractor (`attractor.w <= 0.0` disables it, mirroring `ParticleGpu`'s
+/// own dead-particle convention) integrate into `vel`, while `dt` advances `pos` and the
+/// remaining-lifetime counter packed into `pos.w`.
+#[repr(C, packed)]
+pub struct ParticleSimPushConstants {
+    pub gravity: Vec4,
+    pub attractor: Vec4,
+    pub dt: f32,
+    pub particle_count: u32,
+}
+
+/// GPU particle simulation built on `ComputeTarget`'s sibling, storage-buffer-only
+/// `ComputePipelineBuilder`: two buffers of `capacity` `ParticleGpu`s ping-pong between compute
+/// dispatches (A -> B, then B -> A), and whichever buffer the last dispatch wrote is drawn
+/// directly as a `POINT_LIST` vertex buffer with no `Mesh`/index buffer involved.
+pub struct ParticleSystem {
+    device: ash::Device,
+    buffer_a: vk::Buffer,
+    memory_a: vk::DeviceMemory,
+    buffer_b: vk::Buffer,
+    memory_b: vk::DeviceMemory,
+    sim: ComputePipeline,
+    draw_pipeline: Pipeline,
+    capacity: u32,
+    local_size_x: u32,
+    /// `false`: the last dispatch wrote `buffer_b` (or none has run yet, so both are identical
+    /// seed data). `true`: the last dispatch wrote `buffer_a`.
+    wrote_a: bool,
+}
+
+impl ParticleSystem {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        device: &ash::Device,
+        device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+        command_pool: vk::CommandPool,
+        queue: vk::Queue,
+        sim_shader_compiled: &[u8],
+        vert_shader_compiled: &[u8],
+        frag_shader_compiled: &[u8],
+        render_pass: vk::RenderPass,
+        subpass: u32,
+        capacity: u32,
+        local_size_x: u32,
+        pipeline_cache: vk::PipelineCache,
+    ) -> Self {
+        let seed = vec![ParticleGpu::dead(); capacity as usize];
+        let buffer_usage =
+            vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::VERTEX_BUFFER;
+
+        let (buffer_a, memory_a) = create_buffer_of_type(
+            device,
+            device_mem_properties,
+            command_pool,
+            queue,
+            buffer_usage,
+            &seed,
+        );
+        let (buffer_b, memory_b) = create_buffer_of_type(
+            device,
+            device_mem_properties,
+            command_pool,
+            queue,
+            buffer_usage,
+            &seed,
+        );
+
+        let buffer_size = (capacity as u64) * size_of::<ParticleGpu>() as u64;
+
+        let a_then_b = [(buffer_a, buffer_size), (buffer_b, buffer_size)];
+        let b_then_a = [(buffer_b, buffer_size), (buffer_a, buffer_size)];
+
+        let push_const_range = create_push_const_range(
+            to_u32(size_of::<ParticleSimPushConstants>()),
+            vk::ShaderStageFlags::COMPUTE,
+        );
+
+        let sim = ComputePipelineBuilder::new(device, sim_shader_compiled, 2)
+            .with_storage_buffers(&a_then_b)
+            .with_storage_buffers(&b_then_a)
+            .with_push_const_range(push_const_range)
+            .build();
+
+        let draw_pipeline = build_draw_pipeline(
+            device,
+            vert_shader_compiled,
+            frag_shader_compiled,
+            render_pass,
+            subpass,
+            pipeline_cache,
+        );
+
+        Self {
+            device: device.clone(),
+            buffer_a,
+            memory_a,
+            buffer_b,
+            memory_b,
+            sim,
+            draw_pipeline,
+            capacity,
+            local_size_x,
+            wrote_a: false,
+        }
+    }
+
+    /// The buffer the most recent `step` wrote (or the identical seed data, if `step` was never
+    /// called), ready to either feed next step's read binding or be drawn directly.
+    pub fn current_buffer(&self) -> vk::Buffer {
+        if self.wrote_a { self.buffer_a } else { self.buffer_b }
+    }
+
+    /// How many particles this system simulates and draws, for building this frame's
+    /// `ParticleSimPushConstants::particle_count`.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Dispatches one simulation step: reads whichever buffer `current_buffer` names, writes the
+    /// other, then flips `current_buffer` to it. Must be recorded before the render pass that
+    /// will later draw `current_buffer` begins, with a `barrier_before_draw` in between.
+    pub fn step(&mut self, cmd_buffer: vk::CommandBuffer, push_consts: &ParticleSimPushConstants) {
+        let desc_set_index = usize::from(self.wrote_a);
+
+        unsafe {
+            self.sim.push_constants(cmd_buffer, any_as_bytes(push_consts));
+        }
+
+        let group_count_x = self.capacity.div_ceil(self.local_size_x);
+        self.sim.dispatch(cmd_buffer, desc_set_index, group_count_x, 1, 1);
+
+        self.wrote_a = !self.wrote_a;
+    }
+
+    /// Must be recorded between `step` and any `cmd_bind_vertex_buffers`/`cmd_draw` that reads
+    /// `current_buffer`, since the draw is a vertex-shader read of data a compute shader just
+    /// wrote, and nothing else orders the two without an explicit barrier.
+    pub fn barrier_before_draw(&self, cmd_buffer: vk::CommandBuffer) {
+        let barrier = vk::MemoryBarrier {
+            src_access_mask: vk::AccessFlags::SHADER_WRITE,
+            dst_access_mask: vk::AccessFlags::VERTEX_ATTRIBUTE_READ,
+            ..Default::default()
+        };
+
+        unsafe {
+            self.device.cmd_pipeline_barrier(
+                cmd_buffer,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::PipelineStageFlags::VERTEX_INPUT,
+                vk::DependencyFlags::empty(),
+                &[barrier],
+                &[],
+                &[],
+            );
+        }
+    }
+
+    /// Binds `current_buffer` as vertex binding 0 and issues `cmd_draw` for all `capacity`
+    /// particles as `POINT_LIST` topology, after pushing `view_proj` to the vertex stage.
+    pub fn record_draw(&self, cmd_buffer: vk::CommandBuffer, view_proj: Mat4) {
+        unsafe {
+            self.device.cmd_bind_pipeline(
+                cmd_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.draw_pipeline.inner,
+            );
+
+            self.device.cmd_push_constants(
+                cmd_buffer,
+                self.draw_pipeline.layout,
+                vk::ShaderStageFlags::VERTEX,
+                0,
+                any_as_bytes(&view_proj),
+            );
+
+            self.device.cmd_bind_vertex_buffers(cmd_buffer, 0, &[self.current_buffer()], &[0]);
+            self.device.cmd_draw(cmd_buffer, self.capacity, 1, 0, 0);
+        }
+    }
+}
+
+impl Drop for ParticleSystem {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.destroy_buffer(self.buffer_a, None);
+            self.device.free_memory(self.memory_a, None);
+            self.device.destroy_buffer(self.buffer_b, None);
+            self.device.free_memory(self.memory_b, None);
+        }
+    }
+}
+
+fn build_draw_pipeline(
+    device: &ash::Device,
+    vert_shader_compiled: &[u8],
+    frag_shader_compiled: &[u8],
+    render_pass: vk::RenderPass,
+    subpass: u32,
+    pipeline_cache: vk::PipelineCache,
+) -> Pipeline {
+    let push_const_range =
+        create_push_const_range(to_u32(size_of::<Mat4>()), vk::ShaderStageFlags::VERTEX);
+
+    let mut builder = PipelineBuilder::new(device, render_pass, Some(&push_const_range), None);
+
+    builder
+        .with_subpass(subpass)
+        .with_topology(vk::PrimitiveTopology::POINT_LIST)
+        .with_stride_exact(to_u32(size_of::<ParticleGpu>()))
+        .add_vertex_desc(vk::VertexInputAttributeDescription {
+            location: 0,
+            binding: 0,
+            format: vk::Format::R32G32B32A32_SFLOAT,
+            offset: 0,
+        })
+        .add_vertex_desc(vk::VertexInputAttributeDescription {
+            location: 1,
+            binding: 0,
+            format: vk::Format::R32G32B32A32_SFLOAT,
+            offset: to_u32(size_of::<Vec4>()),
+        });
+
+    if pipeline_cache != vk::PipelineCache::null() {
+        builder.with_pipeline_cache_handle(pipeline_cache);
+    }
+
+    let vert_shader = ShaderModule::new(device, vert_shader_compiled);
+    let frag_shader = ShaderModule::new(device, frag_shader_compiled);
+
+    builder.build(&vert_shader, &frag_shader)
+}
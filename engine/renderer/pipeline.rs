@@ -3,7 +3,7 @@ use std::ptr;
 
 use ash::vk;
 
-use super::{CheckError, SIZE_F32};
+use super::{CheckError, PipelineCache, SIZE_F32};
 use crate::utils::*;
 
 const SHADER_ENTRYPOINT: &CStr = cstr(b"main\0");
@@ -12,11 +12,21 @@ pub struct PipelineBuilder {
     device: ash::Device,
     stride: u32,
     vertex_descs: Vec<vk::VertexInputAttributeDescription>,
+    instance_stride: Option<u32>,
+    instance_descs: Vec<vk::VertexInputAttributeDescription>,
     topology: vk::PrimitiveTopology,
     polygon_mode: vk::PolygonMode,
     pipeline_layout: vk::PipelineLayout,
     render_pass: vk::RenderPass,
     subpass: u32,
+    depth_compare_op: vk::CompareOp,
+    depth_write_enable: bool,
+    cull_mode: vk::CullModeFlags,
+    stencil_state: Option<vk::StencilOpState>,
+    pipeline_cache: vk::PipelineCache,
+    vert_specialization: Option<SpecializationConstants>,
+    frag_specialization: Option<SpecializationConstants>,
+    color_attachments: Vec<vk::PipelineColorBlendAttachmentState>,
 }
 
 pub struct Pipeline {
@@ -31,14 +41,29 @@ pub struct ShaderModule {
 }
 
 impl Pipeline {
+    /// Wraps an already-created `vk::Pipeline`/`vk::PipelineLayout` pair, for pipeline kinds
+    /// (e.g. `RayTracePipeline`) built outside this module whose creation doesn't otherwise fit
+    /// `new_compute`'s shape.
+    pub(super) fn from_raw(
+        device: &ash::Device,
+        inner: vk::Pipeline,
+        layout: vk::PipelineLayout,
+    ) -> Self {
+        Self { device: device.clone(), inner, layout }
+    }
+
     pub fn new_compute(
         device: &ash::Device,
         push_const_range: Option<&vk::PushConstantRange>,
         desc_set_layout: vk::DescriptorSetLayout,
         shader_compiled: &[u8],
+        pipeline_cache: vk::PipelineCache,
+        specialization: Option<SpecializationConstants>,
     ) -> Self {
         let shader = ShaderModule::new(device, shader_compiled);
-        let stage = shader_stage_info(&shader, vk::ShaderStageFlags::COMPUTE);
+        let specialization_info = specialization.as_ref().map(SpecializationConstants::info);
+        let stage =
+            shader_stage_info(&shader, vk::ShaderStageFlags::COMPUTE, specialization_info.as_ref());
         let layout = create_pipeline_layout(device, push_const_range, Some(&desc_set_layout));
 
         let create_info = vk::ComputePipelineCreateInfo {
@@ -47,9 +72,8 @@ impl Pipeline {
             ..Default::default()
         };
 
-        let res = unsafe {
-            device.create_compute_pipelines(vk::PipelineCache::null(), &[create_info], None)
-        };
+        let res =
+            unsafe { device.create_compute_pipelines(pipeline_cache, &[create_info], None) };
 
         let inner = match res {
             Ok(pipelines) => pipelines[0],
@@ -64,6 +88,37 @@ impl Pipeline {
     }
 }
 
+/// Binds `pipeline` and `desc_sets` into `cmd_buffer` and records a dispatch. Standalone
+/// counterpart to `ComputeTarget`'s fixed-size screen pass, for GPU-driven work (e.g. a
+/// particle/physics storage buffer updated each frame and read by the graphics pipeline) that
+/// just needs a pipeline, its descriptor sets and a group count.
+pub fn dispatch(
+    device: &ash::Device,
+    cmd_buffer: vk::CommandBuffer,
+    pipeline: &Pipeline,
+    desc_sets: &[vk::DescriptorSet],
+    group_count_x: u32,
+    group_count_y: u32,
+    group_count_z: u32,
+) {
+    unsafe {
+        device.cmd_bind_pipeline(cmd_buffer, vk::PipelineBindPoint::COMPUTE, pipeline.inner);
+
+        if !desc_sets.is_empty() {
+            device.cmd_bind_descriptor_sets(
+                cmd_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                pipeline.layout,
+                0,
+                desc_sets,
+                &[],
+            );
+        }
+
+        device.cmd_dispatch(cmd_buffer, group_count_x, group_count_y, group_count_z);
+    }
+}
+
 impl PipelineBuilder {
     pub fn new(
         device: &ash::Device,
@@ -74,20 +129,40 @@ impl PipelineBuilder {
         let device = device.clone();
         let stride = 0;
         let vertex_descs = vec![];
+        let instance_stride = None;
+        let instance_descs = vec![];
         let topology = vk::PrimitiveTopology::TRIANGLE_LIST;
         let polygon_mode = vk::PolygonMode::FILL;
         let pipeline_layout = create_pipeline_layout(&device, push_const_range, desc_set_layout);
         let subpass = 0;
+        let depth_compare_op = vk::CompareOp::LESS;
+        let depth_write_enable = true;
+        let cull_mode = vk::CullModeFlags::BACK;
+        let stencil_state = None;
+        let pipeline_cache = vk::PipelineCache::null();
+        let vert_specialization = None;
+        let frag_specialization = None;
+        let color_attachments = vec![];
 
         Self {
             device,
             stride,
             vertex_descs,
+            instance_stride,
+            instance_descs,
             topology,
             polygon_mode,
             pipeline_layout,
             render_pass,
             subpass,
+            depth_compare_op,
+            depth_write_enable,
+            cull_mode,
+            stencil_state,
+            pipeline_cache,
+            vert_specialization,
+            frag_specialization,
+            color_attachments,
         }
     }
 
@@ -133,6 +208,19 @@ impl PipelineBuilder {
         self
     }
 
+    /// Adds a second vertex input binding (binding 1) advancing once per instance instead of once
+    /// per vertex, e.g. for a per-instance transform/color buffer drawn alongside the mesh's own
+    /// binding-0 vertices via `cmd_draw_indexed`'s `instance_count`.
+    pub fn with_instance_binding(
+        &mut self,
+        stride: u32,
+        descs: Vec<vk::VertexInputAttributeDescription>,
+    ) -> &mut Self {
+        self.instance_stride = Some(stride);
+        self.instance_descs = descs;
+        self
+    }
+
     pub fn with_topology(&mut self, topology: vk::PrimitiveTopology) -> &mut Self {
         self.topology = topology;
         self
@@ -149,29 +237,151 @@ impl PipelineBuilder {
         self
     }
 
-    pub fn build(&self, vert_shader: &ShaderModule, frag_shader: &ShaderModule) -> Pipeline {
-        let vert_shader_stage = shader_stage_info(vert_shader, vk::ShaderStageFlags::VERTEX);
-        let frag_shader_stage = shader_stage_info(frag_shader, vk::ShaderStageFlags::FRAGMENT);
+    /// Configures depth/cull state for a skybox drawn last against the far plane, instead of
+    /// fighting the default opaque-geometry depth test. Pair with a vertex shader that strips
+    /// translation from the view matrix and emits clip position as `.xyww` so every fragment lands
+    /// at `z/w == 1.0`: `LESS_OR_EQUAL` then lets the skybox fill only pixels no geometry claimed,
+    /// `depth_write_enable: false` keeps it from occluding anything drawn after it, and culling is
+    /// disabled since the cube is viewed from its inside face.
+    pub fn with_skybox_depth(&mut self) -> &mut Self {
+        self.depth_compare_op = vk::CompareOp::LESS_OR_EQUAL;
+        self.depth_write_enable = false;
+        self.cull_mode = vk::CullModeFlags::NONE;
+        self
+    }
+
+    /// Enables the stencil test with `state` applied to both the front and back face. Used in
+    /// pairs for a two-pass silhouette outline: a first pass that stamps a reference value
+    /// everywhere an object covers (`pass_op: REPLACE`, `write_mask: 0xFF`), and a second pass
+    /// drawing an extruded copy of the same mesh with `compare_op: NOT_EQUAL` and `write_mask: 0`
+    /// so only the border ring outside the first pass's silhouette survives.
+    pub fn with_stencil(&mut self, state: vk::StencilOpState) -> &mut Self {
+        self.stencil_state = Some(state);
+        self
+    }
+
+    /// Turns depth writes on or off (per `enable`) without touching the depth compare op, e.g.
+    /// `false` for an outline pass that should still be depth-tested against the scene but never
+    /// occlude what's drawn after it.
+    pub fn with_depth_write(&mut self, enable: bool) -> &mut Self {
+        self.depth_write_enable = enable;
+        self
+    }
+
+    /// Supplies a `PipelineCache` whose on-disk blob lets the driver skip recompiling shader
+    /// variants it's already seen in a previous run.
+    pub fn with_pipeline_cache(&mut self, pipeline_cache: &PipelineCache) -> &mut Self {
+        self.pipeline_cache = pipeline_cache.inner;
+        self
+    }
+
+    /// Like `with_pipeline_cache`, but from an already-unwrapped handle, for callers (e.g.
+    /// `MeshData::reload_shaders`) that only kept the handle around rather than the owning
+    /// `PipelineCache`.
+    pub fn with_pipeline_cache_handle(&mut self, pipeline_cache: vk::PipelineCache) -> &mut Self {
+        self.pipeline_cache = pipeline_cache;
+        self
+    }
+
+    /// Specializes the vertex shader stage, e.g. to bake in a quality toggle or light count.
+    pub fn with_vert_specialization(&mut self, consts: SpecializationConstants) -> &mut Self {
+        self.vert_specialization = Some(consts);
+        self
+    }
 
-        let binding_desc = vk::VertexInputBindingDescription {
+    /// Specializes the fragment shader stage.
+    pub fn with_frag_specialization(&mut self, consts: SpecializationConstants) -> &mut Self {
+        self.frag_specialization = Some(consts);
+        self
+    }
+
+    /// Adds an opaque (no blending) color attachment output. Attachments are fed to
+    /// `p_attachments` in the order they're added here, which must match the fragment shader's
+    /// `layout(location = N) out` declarations, e.g. for a deferred G-buffer pass writing albedo,
+    /// normals and material data from the same subpass.
+    pub fn add_color_attachment_opaque(&mut self) -> &mut Self {
+        self.color_attachments.push(no_color_blending());
+        self
+    }
+
+    /// Adds a standard alpha-blended color attachment output (`src * src_factor + dst *
+    /// dst_factor`), e.g. for a forward transparent pass.
+    pub fn add_color_attachment_alpha_blend(
+        &mut self,
+        src_factor: vk::BlendFactor,
+        dst_factor: vk::BlendFactor,
+    ) -> &mut Self {
+        self.color_attachments.push(vk::PipelineColorBlendAttachmentState {
+            blend_enable: vk::TRUE,
+            src_color_blend_factor: src_factor,
+            dst_color_blend_factor: dst_factor,
+            color_blend_op: vk::BlendOp::ADD,
+            src_alpha_blend_factor: vk::BlendFactor::ONE,
+            dst_alpha_blend_factor: vk::BlendFactor::ZERO,
+            alpha_blend_op: vk::BlendOp::ADD,
+            color_write_mask: vk::ColorComponentFlags::RGBA,
+        });
+        self
+    }
+
+    pub fn build(&self, vert_shader: &ShaderModule, frag_shader: &ShaderModule) -> Pipeline {
+        let vert_specialization_info =
+            self.vert_specialization.as_ref().map(SpecializationConstants::info);
+        let frag_specialization_info =
+            self.frag_specialization.as_ref().map(SpecializationConstants::info);
+
+        let vert_shader_stage = shader_stage_info(
+            vert_shader,
+            vk::ShaderStageFlags::VERTEX,
+            vert_specialization_info.as_ref(),
+        );
+        let frag_shader_stage = shader_stage_info(
+            frag_shader,
+            vk::ShaderStageFlags::FRAGMENT,
+            frag_specialization_info.as_ref(),
+        );
+
+        let mut binding_descs = vec![vk::VertexInputBindingDescription {
             binding: 0,
             stride: self.stride,
             input_rate: vk::VertexInputRate::VERTEX,
-        };
+        }];
+
+        if let Some(instance_stride) = self.instance_stride {
+            binding_descs.push(vk::VertexInputBindingDescription {
+                binding: 1,
+                stride: instance_stride,
+                input_rate: vk::VertexInputRate::INSTANCE,
+            });
+        }
+
+        let attribute_descs: Vec<_> =
+            self.vertex_descs.iter().chain(&self.instance_descs).copied().collect();
 
         let shader_stages = [vert_shader_stage, frag_shader_stage];
 
         let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
 
-        let vertex_input_state = vertex_input_state_info(&binding_desc, &self.vertex_descs);
+        let vertex_input_state = vertex_input_state_info(&binding_descs, &attribute_descs);
         let input_assembly_state = default_input_assembly(self.topology);
         let viewport_state = viewport_state_info();
-        let rasterization_state = rasterization_info(self.polygon_mode);
+        let rasterization_state = rasterization_info(self.polygon_mode, self.cull_mode);
         let multisample_state = no_multisampling();
-        let stencil_state = no_stencil_state();
-        let depth_state = depth_test(stencil_state);
-        let color_blend_attachment = no_color_blending();
-        let color_blend_state = color_blend_info(&color_blend_attachment);
+        let stencil_state = self.stencil_state.unwrap_or_else(no_stencil_state);
+        let stencil_test_enable = self.stencil_state.is_some();
+        let depth_state = depth_test(
+            stencil_state,
+            stencil_test_enable,
+            self.depth_compare_op,
+            self.depth_write_enable,
+        );
+        let default_color_attachments = [no_color_blending()];
+        let color_blend_attachments = if self.color_attachments.is_empty() {
+            &default_color_attachments[..]
+        } else {
+            &self.color_attachments[..]
+        };
+        let color_blend_state = color_blend_info(color_blend_attachments);
         let dynamic_state = dynamic_state_info(&dynamic_states);
 
         let create_info = [vk::GraphicsPipelineCreateInfo {
@@ -193,7 +403,7 @@ impl PipelineBuilder {
         }];
 
         let graphics_pipelines = unsafe {
-            self.device.create_graphics_pipelines(vk::PipelineCache::null(), &create_info, None)
+            self.device.create_graphics_pipelines(self.pipeline_cache, &create_info, None)
         };
 
         let inner = match graphics_pipelines {
@@ -245,19 +455,61 @@ impl Drop for ShaderModule {
     }
 }
 
-fn shader_stage_info(
+/// Collects `(constant_id, value)` pairs into a packed data buffer plus the matching
+/// `vk::SpecializationMapEntry`s, to back a shader stage's `vk::SpecializationInfo`. Lets one
+/// SPIR-V module (e.g. a compute shader reading `local_size_x/y/z` from a spec constant) be
+/// instantiated as several pipeline variants without a recompile.
+#[derive(Default)]
+pub struct SpecializationConstants {
+    entries: Vec<vk::SpecializationMapEntry>,
+    data: Vec<u8>,
+}
+
+impl SpecializationConstants {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add<T: Copy>(mut self, constant_id: u32, value: T) -> Self {
+        let offset = to_u32(self.data.len());
+        let size = std::mem::size_of::<T>();
+
+        self.data.extend_from_slice(unsafe { any_as_bytes(&value) });
+        self.entries.push(vk::SpecializationMapEntry { constant_id, offset, size });
+
+        self
+    }
+
+    fn info(&self) -> vk::SpecializationInfo {
+        vk::SpecializationInfo {
+            map_entry_count: to_u32(self.entries.len()),
+            p_map_entries: self.entries.as_ptr(),
+            data_size: self.data.len(),
+            p_data: self.data.as_ptr().cast(),
+        }
+    }
+}
+
+pub(super) fn shader_stage_info(
     module: &ShaderModule,
     stage: vk::ShaderStageFlags,
+    specialization: Option<&vk::SpecializationInfo>,
 ) -> vk::PipelineShaderStageCreateInfo {
+    let p_specialization_info = match specialization {
+        Some(info) => info as *const _,
+        None => ptr::null(),
+    };
+
     vk::PipelineShaderStageCreateInfo {
         stage,
         module: module.inner,
         p_name: SHADER_ENTRYPOINT.as_ptr(),
+        p_specialization_info,
         ..Default::default()
     }
 }
 
-fn create_pipeline_layout(
+pub(super) fn create_pipeline_layout(
     device: &ash::Device,
     push_const_range: Option<&vk::PushConstantRange>,
     desc_set_layout: Option<&vk::DescriptorSetLayout>,
@@ -284,14 +536,14 @@ fn create_pipeline_layout(
 }
 
 fn vertex_input_state_info(
-    binding_desc: &vk::VertexInputBindingDescription,
-    attribute_desc: &[vk::VertexInputAttributeDescription],
+    binding_descs: &[vk::VertexInputBindingDescription],
+    attribute_descs: &[vk::VertexInputAttributeDescription],
 ) -> vk::PipelineVertexInputStateCreateInfo {
     vk::PipelineVertexInputStateCreateInfo {
-        vertex_binding_description_count: 1,
-        p_vertex_binding_descriptions: binding_desc,
-        vertex_attribute_description_count: to_u32(attribute_desc.len()),
-        p_vertex_attribute_descriptions: attribute_desc.as_ptr(),
+        vertex_binding_description_count: to_u32(binding_descs.len()),
+        p_vertex_binding_descriptions: binding_descs.as_ptr(),
+        vertex_attribute_description_count: to_u32(attribute_descs.len()),
+        p_vertex_attribute_descriptions: attribute_descs.as_ptr(),
         ..Default::default()
     }
 }
@@ -314,12 +566,15 @@ fn viewport_state_info() -> vk::PipelineViewportStateCreateInfo {
     }
 }
 
-fn rasterization_info(polygon_mode: vk::PolygonMode) -> vk::PipelineRasterizationStateCreateInfo {
+fn rasterization_info(
+    polygon_mode: vk::PolygonMode,
+    cull_mode: vk::CullModeFlags,
+) -> vk::PipelineRasterizationStateCreateInfo {
     vk::PipelineRasterizationStateCreateInfo {
         depth_clamp_enable: vk::FALSE,
         rasterizer_discard_enable: vk::FALSE,
         polygon_mode,
-        cull_mode: vk::CullModeFlags::BACK,
+        cull_mode,
         front_face: vk::FrontFace::COUNTER_CLOCKWISE,
         depth_bias_enable: vk::FALSE,
         line_width: 1.0,
@@ -351,15 +606,20 @@ const fn no_stencil_state() -> vk::StencilOpState {
     }
 }
 
-fn depth_test(stencil_state: vk::StencilOpState) -> vk::PipelineDepthStencilStateCreateInfo {
+fn depth_test(
+    stencil_state: vk::StencilOpState,
+    stencil_test_enable: bool,
+    depth_compare_op: vk::CompareOp,
+    depth_write_enable: bool,
+) -> vk::PipelineDepthStencilStateCreateInfo {
     vk::PipelineDepthStencilStateCreateInfo {
         depth_test_enable: vk::TRUE,
-        depth_write_enable: vk::TRUE,
-        depth_compare_op: vk::CompareOp::LESS,
+        depth_write_enable: if depth_write_enable { vk::TRUE } else { vk::FALSE },
+        depth_compare_op,
         depth_bounds_test_enable: vk::FALSE,
         min_depth_bounds: 0.0,
         max_depth_bounds: 1.0,
-        stencil_test_enable: vk::FALSE,
+        stencil_test_enable: if stencil_test_enable { vk::TRUE } else { vk::FALSE },
         front: stencil_state,
         back: stencil_state,
         ..Default::default()
@@ -375,12 +635,12 @@ fn no_color_blending() -> vk::PipelineColorBlendAttachmentState {
 }
 
 fn color_blend_info(
-    color_blend_attachment: &vk::PipelineColorBlendAttachmentState,
+    color_blend_attachments: &[vk::PipelineColorBlendAttachmentState],
 ) -> vk::PipelineColorBlendStateCreateInfo {
     vk::PipelineColorBlendStateCreateInfo {
         logic_op_enable: vk::FALSE,
-        attachment_count: 1,
-        p_attachments: color_blend_attachment,
+        attachment_count: to_u32(color_blend_attachments.len()),
+        p_attachments: color_blend_attachments.as_ptr(),
         ..Default::default()
     }
 }
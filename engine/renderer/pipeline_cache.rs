@@ -0,0 +1,80 @@
+use ash::vk;
+use log::warn;
+
+use super::*;
+use crate::utils::*;
+
+/// Bytes of a `VkPipelineCacheHeaderVersionOne` header that precede the driver-specific payload:
+/// `headerSize`, `headerVersion`, `vendorID`, `deviceID` (4 bytes each) followed by a 16-byte
+/// `pipelineCacheUUID`.
+const HEADER_LEN: usize = 4 + 4 + 4 + 4 + 16;
+
+/// Wraps a `vk::PipelineCache` seeded from a blob left on disk by a previous run, so pipeline
+/// creation doesn't have to recompile from scratch every launch. The blob is keyed by the
+/// driver's vendor/device ID and pipeline-cache UUID; a cache saved by a different GPU or driver
+/// version is discarded rather than fed back in, since the driver gives no guarantee it'll even
+/// look at mismatched data. On drop, the (possibly now larger) cache contents are serialized back
+/// to `path`.
+pub struct PipelineCache {
+    device: ash::Device,
+    path: &'static str,
+    pub inner: vk::PipelineCache,
+}
+
+impl PipelineCache {
+    pub fn new(
+        device: &ash::Device,
+        properties: &vk::PhysicalDeviceProperties,
+        path: &'static str,
+    ) -> Self {
+        let on_disk = std::fs::read(path).ok();
+        let initial_data = on_disk.filter(|data| cache_header_matches(data, properties));
+
+        let create_info = vk::PipelineCacheCreateInfo {
+            initial_data_size: initial_data.as_ref().map_or(0, Vec::len),
+            p_initial_data: initial_data.as_ref().map_or(std::ptr::null(), |d| d.as_ptr().cast()),
+            ..Default::default()
+        };
+
+        let inner = unsafe { device.create_pipeline_cache(&create_info, None) }
+            .check_err("create pipeline cache");
+
+        Self {
+            device: device.clone(),
+            path,
+            inner,
+        }
+    }
+}
+
+/// Checks a serialized cache blob's header against the current device's identity, per the
+/// `VkPipelineCacheHeaderVersionOne` layout: a matching `vendorID`/`deviceID`/`pipelineCacheUUID`
+/// is the driver's documented precondition for it to actually reuse the payload.
+fn cache_header_matches(data: &[u8], properties: &vk::PhysicalDeviceProperties) -> bool {
+    if data.len() < HEADER_LEN {
+        return false;
+    }
+
+    let vendor_id = u32::from_le_bytes([data[8], data[9], data[10], data[11]]);
+    let device_id = u32::from_le_bytes([data[12], data[13], data[14], data[15]]);
+    let uuid = &data[16..32];
+
+    vendor_id == properties.vendor_id
+        && device_id == properties.device_id
+        && uuid == &properties.pipeline_cache_uuid[..]
+}
+
+impl Drop for PipelineCache {
+    fn drop(&mut self) {
+        match unsafe { self.device.get_pipeline_cache_data(self.inner) } {
+            Ok(data) => {
+                if let Err(e) = std::fs::write(self.path, data) {
+                    warn!("failed to write pipeline cache to {}: {e}", self.path);
+                }
+            }
+            Err(e) => warn!("failed to get pipeline cache data: {e}"),
+        }
+
+        unsafe { self.device.destroy_pipeline_cache(self.inner, None) };
+    }
+}
@@ -0,0 +1,361 @@
+use ash::vk;
+
+use super::vulkan::*;
+use super::*;
+use crate::utils::*;
+
+/// Which texture a post-processing pass samples for a given input binding.
+#[derive(Clone, Copy)]
+pub enum PostPassInput {
+    /// The immediately preceding pass's output (the scene render, for the first pass).
+    Source,
+    /// The original, unprocessed scene render, regardless of how many passes ran since.
+    Original,
+}
+
+/// How a post-processing pass filters the texture(s) it samples.
+#[derive(Clone, Copy)]
+pub enum FilterMode {
+    Nearest,
+    Linear,
+}
+
+impl FilterMode {
+    const fn to_vk(self) -> vk::Filter {
+        match self {
+            Self::Nearest => vk::Filter::NEAREST,
+            Self::Linear => vk::Filter::LINEAR,
+        }
+    }
+}
+
+/// A pass's target resolution, either relative to the resolution it reads from (e.g. `0.5` for a
+/// half-res bloom downsample) or given in absolute pixels.
+#[derive(Clone, Copy)]
+pub enum Scale {
+    Relative(f32),
+    Absolute(u32, u32),
+}
+
+impl Scale {
+    fn resolve(self, source: (u32, u32)) -> (u32, u32) {
+        match self {
+            Self::Relative(factor) => {
+                let scale_dim = |dim: u32| {
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    let scaled = (to_f32(dim) * factor).round() as u32;
+
+                    scaled.max(1)
+                };
+
+                (scale_dim(source.0), scale_dim(source.1))
+            }
+            Self::Absolute(width, height) => (width, height),
+        }
+    }
+}
+
+/// A texture a pass reads from: either a single texture reused every frame (e.g. the stand-in
+/// scene texture described below) or one of a previous pass's per-frame-in-flight outputs.
+#[derive(Clone, Copy)]
+pub enum PostPassSource<'t> {
+    Static(&'t Texture),
+    PerFrame(&'t [Texture]),
+}
+
+/// One stage of a RetroArch-slang-style post-processing chain: a fullscreen-quad draw that reads
+/// its configured `PostPassInput`s and writes to its own offscreen color attachment, which the
+/// next pass (or the same pass on the following frame) then samples in turn.
+///
+/// This owns the full render-pass/framebuffer/mesh machinery needed to run a pass in isolation.
+/// `Renderer::new` adds one passthrough stage so this actually records and submits real GPU work
+/// every frame. `Source`/`Original` are bound to `Renderer::scene_capture`: a copy of the just-
+/// rendered swapchain image, taken every frame in `record_commands` right after the main scene
+/// pass ends (the main scene pass itself still renders straight to the swapchain rather than to a
+/// sampleable offscreen attachment, so this copy is how the chain gets something to sample at
+/// all). `record_commands` also composites the chain's final output back onto the swapchain image
+/// (via `record_present_blit`) before presenting, so the chain's result is actually what's shown.
+pub struct PostPass {
+    device: ash::Device,
+    targets: Vec<Texture>,
+    render_pass: vk::RenderPass,
+    framebuffers: Vec<vk::Framebuffer>,
+    mesh: MeshData,
+    extent: vk::Extent2D,
+}
+
+impl PostPass {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        device: &ash::Device,
+        device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+        command_pool: vk::CommandPool,
+        graphics_queue: vk::Queue,
+        format: vk::Format,
+        source_extent: vk::Extent2D,
+        scale: Scale,
+        filter: FilterMode,
+        inputs: &[PostPassInput],
+        frag_shader_compiled: &[u8],
+        source: PostPassSource,
+        original: PostPassSource,
+        per_frame_copies: usize,
+        pipeline_cache: &PipelineCache,
+        debug_data: Option<&DebugData>,
+    ) -> Self {
+        let (width, height) = scale.resolve((source_extent.width, source_extent.height));
+        let extent = vk::Extent2D { width, height };
+
+        let mut targets = Vec::with_capacity(per_frame_copies);
+
+        for i in 0..per_frame_copies {
+            let target = Texture::new_render_target(
+                device,
+                device_mem_properties,
+                format,
+                width,
+                height,
+                1,
+                filter.to_vk(),
+                debug_data,
+                &format!("post pass {i}"),
+            );
+
+            targets.push(target);
+        }
+
+        let render_pass = RenderPassBuilder::new()
+            .with_attachment(
+                format,
+                vk::AttachmentLoadOp::CLEAR,
+                vk::AttachmentStoreOp::STORE,
+                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            )
+            .with_subpass(SubpassBuilder::new().with_color_attachment(0))
+            // Upstream pass's write (whatever produced the sampled input) happens-before this
+            // pass's read and its own color write.
+            .with_dependency(
+                DependencyBuilder::new()
+                    .subpasses(vk::SUBPASS_EXTERNAL, 0)
+                    .stage_masks(
+                        vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                        vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT
+                            | vk::PipelineStageFlags::FRAGMENT_SHADER,
+                    )
+                    .access_masks(
+                        vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+                        vk::AccessFlags::COLOR_ATTACHMENT_WRITE | vk::AccessFlags::SHADER_READ,
+                    )
+                    .build(),
+            )
+            // This pass's write happens-before the next pass (or anything else) sampling it.
+            .with_dependency(
+                DependencyBuilder::new()
+                    .subpasses(0, vk::SUBPASS_EXTERNAL)
+                    .stage_masks(
+                        vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                        vk::PipelineStageFlags::FRAGMENT_SHADER,
+                    )
+                    .access_masks(
+                        vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+                        vk::AccessFlags::SHADER_READ,
+                    )
+                    .build(),
+            )
+            .build(device);
+
+        let framebuffers = targets
+            .iter()
+            .map(|t| {
+                create_single_attachment_framebuffer(device, t.image_view, extent, render_pass)
+            })
+            .collect();
+
+        let mut mesh_builder = Mesh::textured_screen_quad().to_builder(
+            device,
+            device_mem_properties,
+            command_pool,
+            graphics_queue,
+            render_pass,
+            per_frame_copies,
+            include_shader!("textured_screen_quad.vert"),
+            frag_shader_compiled,
+        );
+
+        for input in inputs {
+            mesh_builder = match (input, source, original) {
+                (PostPassInput::Source, PostPassSource::Static(t), _)
+                | (PostPassInput::Original, _, PostPassSource::Static(t)) => {
+                    mesh_builder.with_texture(t)
+                }
+                (PostPassInput::Source, PostPassSource::PerFrame(ts), _)
+                | (PostPassInput::Original, _, PostPassSource::PerFrame(ts)) => {
+                    mesh_builder.with_textures(ts)
+                }
+            };
+        }
+
+        let mesh = mesh_builder.with_pipeline_cache(pipeline_cache).build();
+
+        Self {
+            device: device.clone(),
+            targets,
+            render_pass,
+            framebuffers,
+            mesh,
+            extent,
+        }
+    }
+
+    pub fn record(&self, cmd_buffer: vk::CommandBuffer, current_frame: usize) {
+        let clear_values = [CLEAR_COLOR];
+
+        let render_pass_info = vk::RenderPassBeginInfo {
+            render_pass: self.render_pass,
+            framebuffer: self.framebuffers[current_frame],
+            render_area: vk::Rect2D {
+                offset: vk::Offset2D::default(),
+                extent: self.extent,
+            },
+            clear_value_count: to_u32(clear_values.len()),
+            p_clear_values: clear_values.as_ptr(),
+            ..Default::default()
+        };
+
+        unsafe {
+            self.device.cmd_begin_render_pass(
+                cmd_buffer,
+                &render_pass_info,
+                vk::SubpassContents::INLINE,
+            );
+
+            self.mesh.record_draw_commands(cmd_buffer, current_frame);
+
+            self.device.cmd_end_render_pass(cmd_buffer);
+        }
+    }
+
+    /// This pass's per-frame-in-flight outputs, for the next pass's `Source` input.
+    pub fn textures(&self) -> &[Texture] {
+        &self.targets
+    }
+
+    /// This pass's output resolution, for the next pass's `Scale::Relative` to scale from.
+    pub fn extent(&self) -> vk::Extent2D {
+        self.extent
+    }
+}
+
+impl Drop for PostPass {
+    fn drop(&mut self) {
+        unsafe {
+            for fb in &self.framebuffers {
+                self.device.destroy_framebuffer(*fb, None);
+            }
+
+            self.device.destroy_render_pass(self.render_pass, None);
+        }
+    }
+}
+
+/// An ordered, reorderable chain of `PostPass`es (e.g. downsample -> blur -> bloom composite):
+/// each pass added via `add_pass` reads the previous pass's output as its `Source` (or the
+/// chain's own scene render for the first pass), while `Original` stays pinned to that same scene
+/// render throughout, for any pass in the chain that asks for it via `needs_original`. Scale is
+/// relative to whatever the new pass reads from, so a chain of `Relative(0.5)` passes halves
+/// resolution at each step rather than each being half of the original.
+pub struct PostProcessChain<'a> {
+    device: ash::Device,
+    device_mem_properties: &'a vk::PhysicalDeviceMemoryProperties,
+    command_pool: vk::CommandPool,
+    graphics_queue: vk::Queue,
+    format: vk::Format,
+    source_extent: vk::Extent2D,
+    filter: FilterMode,
+    scene: PostPassSource<'a>,
+    per_frame_copies: usize,
+    pipeline_cache: &'a PipelineCache,
+    debug_data: Option<&'a DebugData>,
+    passes: Vec<PostPass>,
+}
+
+impl<'a> PostProcessChain<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        device: &ash::Device,
+        device_mem_properties: &'a vk::PhysicalDeviceMemoryProperties,
+        command_pool: vk::CommandPool,
+        graphics_queue: vk::Queue,
+        format: vk::Format,
+        source_extent: vk::Extent2D,
+        filter: FilterMode,
+        scene: PostPassSource<'a>,
+        per_frame_copies: usize,
+        pipeline_cache: &'a PipelineCache,
+        debug_data: Option<&'a DebugData>,
+    ) -> Self {
+        Self {
+            device: device.clone(),
+            device_mem_properties,
+            command_pool,
+            graphics_queue,
+            format,
+            source_extent,
+            filter,
+            scene,
+            per_frame_copies,
+            pipeline_cache,
+            debug_data,
+            passes: vec![],
+        }
+    }
+
+    /// Appends a pass sampling `frag_spirv`, sized `scale` relative to whatever it reads from.
+    /// `needs_original` binds the chain's original scene render as a second input alongside the
+    /// previous pass's output, e.g. for a bloom composite that blends a blurred buffer back over
+    /// the unprocessed scene.
+    pub fn add_pass(&mut self, frag_spirv: &[u8], scale: Scale, needs_original: bool) {
+        let (source, source_extent) = self.passes.last().map_or(
+            (self.scene, self.source_extent),
+            |p| (PostPassSource::PerFrame(p.textures()), p.extent()),
+        );
+
+        let mut inputs = vec![PostPassInput::Source];
+        if needs_original {
+            inputs.push(PostPassInput::Original);
+        }
+
+        let pass = PostPass::new(
+            &self.device,
+            self.device_mem_properties,
+            self.command_pool,
+            self.graphics_queue,
+            self.format,
+            source_extent,
+            scale,
+            self.filter,
+            &inputs,
+            frag_spirv,
+            source,
+            self.scene,
+            self.per_frame_copies,
+            self.pipeline_cache,
+            self.debug_data,
+        );
+
+        self.passes.push(pass);
+    }
+
+    /// Records every pass in the chain, in the order they were added.
+    pub fn record(&self, cmd_buffer: vk::CommandBuffer, current_frame: usize) {
+        for pass in &self.passes {
+            pass.record(cmd_buffer, current_frame);
+        }
+    }
+
+    /// The last pass's per-frame-in-flight outputs, i.e. the chain's final image, or `None` if no
+    /// pass has been added yet.
+    pub fn final_textures(&self) -> Option<&[Texture]> {
+        self.passes.last().map(PostPass::textures)
+    }
+}
@@ -0,0 +1,698 @@
+use ash::extensions::khr::{AccelerationStructure, RayTracingPipeline};
+use ash::vk;
+use glam::Mat4;
+
+use super::pipeline::*;
+use super::vulkan::*;
+use super::*;
+use crate::utils::*;
+
+/// Hardware-accelerated alternative to `ComputeTarget`'s SSBO-based `worldSizes`/`worldSpans`
+/// raycasting: builds real `VK_KHR_acceleration_structure` bottom/top-level acceleration
+/// structures out of a mesh's own vertex/index buffers, then traces against them with a
+/// `VK_KHR_ray_tracing_pipeline` pipeline instead of walking spans in a compute shader.
+///
+/// This module assumes `instance`/`device` were already created with
+/// `VK_KHR_acceleration_structure`, `VK_KHR_ray_tracing_pipeline` and their prerequisite
+/// extensions (`VK_KHR_deferred_host_operations`, `VK_KHR_buffer_device_address`, the last of
+/// which is core in Vulkan 1.2 and already enabled via `API_VER_MINOR`) enabled, and the matching
+/// `vk::PhysicalDeviceAccelerationStructureFeaturesKHR`/
+/// `vk::PhysicalDeviceRayTracingPipelineFeaturesKHR` chained into `DeviceCreateInfo` at device
+/// creation. None of that device setup lives here: it's a larger, risk-bearing change to
+/// `create_logical_device`/`OPT_DEVICE_EXTENSIONS` (enabling a feature the rest of the renderer
+/// doesn't otherwise need) that belongs with whoever first wires a `RayTracePipeline` into
+/// `Renderer`, left as a follow-up. The `AccelerationStructure`/`RayTracingPipeline` loaders are
+/// taken as parameters below, built by that caller with `AccelerationStructure::new`/
+/// `RayTracingPipeline::new`, rather than constructed in here.
+///
+/// Status: NOT WIRED, NOT USABLE AS SHIPPED. This whole module — `build_blas`, `TlasBuilder`,
+/// `RayTracePipeline`, and `ShaderAttachment::AccelerationStructure`'s descriptor-write plumbing
+/// in `mesh.rs` — is self-contained library code with no caller. Concretely, three prerequisites
+/// are all still missing, and this is infrastructure, not a feature someone merely forgot to call:
+///
+/// 1. `create_logical_device` enables neither `VK_KHR_acceleration_structure` nor
+///    `VK_KHR_ray_tracing_pipeline` (only `OPT_DEVICE_EXTENSIONS` in `vulkan.rs`, which lists
+///    `VK_KHR_shared_presentable_image`/`VK_KHR_multiview`), so `AccelerationStructure::new`/
+///    `RayTracingPipeline::new` have no loader entry points to call in a real `Renderer`.
+/// 2. `Mesh`'s vertex/index buffers (`mesh.rs`) are created with `VERTEX_BUFFER`/`INDEX_BUFFER`
+///    usage only, never `SHADER_DEVICE_ADDRESS` — so `buffer_device_address` on an existing
+///    mesh's buffers is not just unwired but a Vulkan validation error waiting to happen, not a
+///    couple of lines away from working.
+/// 3. No raygen/miss/closest-hit SPIR-V exists anywhere in this repo for `RayTracePipeline::new`
+///    to load: there's no shader source, compiled binary, or build step producing one.
+///
+/// No mesh builds a BLAS, no scene assembles a TLAS, and `Renderer` never constructs a
+/// `RayTracePipeline` or dispatches a trace. Wiring a real call site is a larger, separate change
+/// (device setup, buffer usage flags, and real shaders) than this module's geometry-math and
+/// Vulkan-object-lifetime code; until that lands, treat this as a staged building block, not a
+/// shipped feature.
+pub struct AccelerationStructureHandle {
+    device: ash::Device,
+    loader: AccelerationStructure,
+    pub inner: vk::AccelerationStructureKHR,
+    pub device_address: vk::DeviceAddress,
+    buffer: vk::Buffer,
+    memory: vk::DeviceMemory,
+}
+
+impl Drop for AccelerationStructureHandle {
+    fn drop(&mut self) {
+        unsafe {
+            self.loader.destroy_acceleration_structure(self.inner, None);
+            self.device.destroy_buffer(self.buffer, None);
+            self.device.free_memory(self.memory, None);
+        }
+    }
+}
+
+/// A mesh's GPU-side triangle data, already built (e.g. via `MeshDataBuilder::build`), in the
+/// shape a BLAS build needs: device addresses rather than buffer handles, since
+/// `VkAccelerationStructureGeometryKHR` reads geometry by address, not by descriptor binding.
+pub struct BlasTriangleData {
+    pub vertex_address: vk::DeviceAddress,
+    pub vertex_stride: u64,
+    pub vertex_count: u32,
+    pub index_address: vk::DeviceAddress,
+    pub index_count: u32,
+}
+
+/// Looks up `buffer`'s device address, for use as a BLAS/TLAS geometry's `deviceAddress` field.
+/// `buffer` must have been created with `vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS`.
+pub fn buffer_device_address(device: &ash::Device, buffer: vk::Buffer) -> vk::DeviceAddress {
+    let info = vk::BufferDeviceAddressInfo {
+        buffer,
+        ..Default::default()
+    };
+
+    unsafe { device.get_buffer_device_address(&info) }
+}
+
+/// Builds a bottom-level acceleration structure over a single triangle mesh (`R32G32B32` vertex
+/// positions, `UINT16` indices). Only a `Mesh` whose `indices` are `Indices::U16` fits this
+/// layout; `Indices::U32` index data is not handled.
+pub fn build_blas(
+    device: &ash::Device,
+    device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+    command_pool: vk::CommandPool,
+    queue: vk::Queue,
+    as_loader: &AccelerationStructure,
+    triangles: &BlasTriangleData,
+) -> AccelerationStructureHandle {
+    let triangles_data = vk::AccelerationStructureGeometryTrianglesDataKHR {
+        vertex_format: vk::Format::R32G32B32_SFLOAT,
+        vertex_data: vk::DeviceOrHostAddressConstKHR { device_address: triangles.vertex_address },
+        vertex_stride: triangles.vertex_stride,
+        max_vertex: triangles.vertex_count.saturating_sub(1),
+        index_type: vk::IndexType::UINT16,
+        index_data: vk::DeviceOrHostAddressConstKHR { device_address: triangles.index_address },
+        ..Default::default()
+    };
+
+    let geometry = vk::AccelerationStructureGeometryKHR {
+        geometry_type: vk::GeometryTypeKHR::TRIANGLES,
+        geometry: vk::AccelerationStructureGeometryDataKHR { triangles: triangles_data },
+        flags: vk::GeometryFlagsKHR::OPAQUE,
+        ..Default::default()
+    };
+
+    let primitive_count = triangles.index_count / 3;
+
+    build_acceleration_structure(
+        device,
+        device_mem_properties,
+        command_pool,
+        queue,
+        as_loader,
+        vk::AccelerationStructureTypeKHR::BOTTOM_LEVEL,
+        vk::BuildAccelerationStructureFlagsKHR::PREFER_FAST_TRACE,
+        &[geometry],
+        &[primitive_count],
+    )
+}
+
+/// Collects `(BLAS, transform)` instances into a top-level acceleration structure, the ray-traced
+/// counterpart of a scene's draw list. Accumulates instances: push every instance, then `build`
+/// once. `build` always sets `ALLOW_UPDATE`, so the result is
+/// ready for `refit` once instance transforms change (e.g. every frame, for a fully dynamic
+/// scene) without a full rebuild.
+#[derive(Default)]
+pub struct TlasBuilder {
+    instances: Vec<vk::AccelerationStructureInstanceKHR>,
+}
+
+impl TlasBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds one instance of `blas`, placed in the scene by `transform` (row-major, as
+    /// `VkAccelerationStructureInstanceKHR` expects, not glam's column-major `Mat4`).
+    pub fn add_instance(
+        &mut self,
+        blas: &AccelerationStructureHandle,
+        transform: Mat4,
+    ) -> &mut Self {
+        let t = transform.transpose().to_cols_array();
+
+        #[allow(clippy::cast_possible_truncation)]
+        let cull_disable_flag =
+            vk::GeometryInstanceFlagsKHR::TRIANGLE_FACING_CULL_DISABLE.as_raw() as u8;
+
+        let instance = vk::AccelerationStructureInstanceKHR {
+            transform: vk::TransformMatrixKHR {
+                matrix: [
+                    [t[0], t[1], t[2], t[3]],
+                    [t[4], t[5], t[6], t[7]],
+                    [t[8], t[9], t[10], t[11]],
+                ],
+            },
+            instance_custom_index_and_mask: vk::Packed24_8::new(0, 0xff),
+            instance_shader_binding_table_record_offset_and_flags: vk::Packed24_8::new(
+                0,
+                cull_disable_flag,
+            ),
+            acceleration_structure_reference: vk::AccelerationStructureReferenceKHR {
+                device_handle: blas.device_address,
+            },
+        };
+
+        self.instances.push(instance);
+        self
+    }
+
+    pub fn build(
+        self,
+        device: &ash::Device,
+        device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+        command_pool: vk::CommandPool,
+        queue: vk::Queue,
+        as_loader: &AccelerationStructure,
+    ) -> AccelerationStructureHandle {
+        let instance_count = to_u32(self.instances.len());
+
+        let (instance_buffer, instance_memory) = create_buffer_of_type(
+            device,
+            device_mem_properties,
+            command_pool,
+            queue,
+            vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS
+                | vk::BufferUsageFlags::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR,
+            &self.instances,
+        );
+
+        let instance_address = buffer_device_address(device, instance_buffer);
+
+        let instances_data = vk::AccelerationStructureGeometryInstancesDataKHR {
+            data: vk::DeviceOrHostAddressConstKHR { device_address: instance_address },
+            ..Default::default()
+        };
+
+        let geometry = vk::AccelerationStructureGeometryKHR {
+            geometry_type: vk::GeometryTypeKHR::INSTANCES,
+            geometry: vk::AccelerationStructureGeometryDataKHR { instances: instances_data },
+            ..Default::default()
+        };
+
+        let tlas = build_acceleration_structure(
+            device,
+            device_mem_properties,
+            command_pool,
+            queue,
+            as_loader,
+            vk::AccelerationStructureTypeKHR::TOP_LEVEL,
+            vk::BuildAccelerationStructureFlagsKHR::ALLOW_UPDATE,
+            &[geometry],
+            &[instance_count],
+        );
+
+        unsafe {
+            device.destroy_buffer(instance_buffer, None);
+            device.free_memory(instance_memory, None);
+        }
+
+        tlas
+    }
+
+    /// Refits `previous` in place for this frame's instance transforms, rather than building a
+    /// new acceleration structure from scratch: cheaper than `build` since it reuses `previous`'s
+    /// existing backing buffer and only recomputes bounds, not the whole BVH. Requires `previous`
+    /// to have come from `build` (or an earlier `refit`) with the same instance count — refitting
+    /// across a change in instance count is not supported by `UPDATE` mode and needs a full
+    /// `build` instead.
+    pub fn refit(
+        self,
+        device: &ash::Device,
+        device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+        command_pool: vk::CommandPool,
+        queue: vk::Queue,
+        as_loader: &AccelerationStructure,
+        previous: &AccelerationStructureHandle,
+    ) {
+        let instance_count = to_u32(self.instances.len());
+
+        let (instance_buffer, instance_memory) = create_buffer_of_type(
+            device,
+            device_mem_properties,
+            command_pool,
+            queue,
+            vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS
+                | vk::BufferUsageFlags::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR,
+            &self.instances,
+        );
+
+        let instance_address = buffer_device_address(device, instance_buffer);
+
+        let instances_data = vk::AccelerationStructureGeometryInstancesDataKHR {
+            data: vk::DeviceOrHostAddressConstKHR { device_address: instance_address },
+            ..Default::default()
+        };
+
+        let geometry = vk::AccelerationStructureGeometryKHR {
+            geometry_type: vk::GeometryTypeKHR::INSTANCES,
+            geometry: vk::AccelerationStructureGeometryDataKHR { instances: instances_data },
+            ..Default::default()
+        };
+
+        let mut build_info = vk::AccelerationStructureBuildGeometryInfoKHR {
+            ty: vk::AccelerationStructureTypeKHR::TOP_LEVEL,
+            flags: vk::BuildAccelerationStructureFlagsKHR::ALLOW_UPDATE,
+            mode: vk::BuildAccelerationStructureModeKHR::UPDATE,
+            src_acceleration_structure: previous.inner,
+            dst_acceleration_structure: previous.inner,
+            geometry_count: 1,
+            p_geometries: &geometry,
+            ..Default::default()
+        };
+
+        let build_sizes = unsafe {
+            as_loader.get_acceleration_structure_build_sizes(
+                vk::AccelerationStructureBuildTypeKHR::DEVICE,
+                &build_info,
+                &[instance_count],
+            )
+        };
+
+        let scratch_usage =
+            vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS | vk::BufferUsageFlags::STORAGE_BUFFER;
+
+        let (scratch_buffer, scratch_memory) = unsafe {
+            create_buffer(
+                device,
+                device_mem_properties,
+                build_sizes.update_scratch_size,
+                scratch_usage,
+                vk::MemoryPropertyFlags::DEVICE_LOCAL,
+            )
+        };
+
+        let scratch_address = buffer_device_address(device, scratch_buffer);
+        build_info.scratch_data = vk::DeviceOrHostAddressKHR { device_address: scratch_address };
+
+        let range_info = vk::AccelerationStructureBuildRangeInfoKHR {
+            primitive_count: instance_count,
+            ..Default::default()
+        };
+        let range_infos: &[vk::AccelerationStructureBuildRangeInfoKHR] = &[range_info];
+
+        unsafe {
+            let cmd_buffer = start_single_command(device, command_pool);
+
+            as_loader.cmd_build_acceleration_structures(cmd_buffer, &[build_info], &[range_infos]);
+
+            end_single_command(device, command_pool, cmd_buffer, queue);
+
+            device.destroy_buffer(scratch_buffer, None);
+            device.free_memory(scratch_memory, None);
+            device.destroy_buffer(instance_buffer, None);
+            device.free_memory(instance_memory, None);
+        }
+    }
+}
+
+/// Shared bottom/top-level build path: sizes the acceleration structure and a scratch buffer via
+/// `get_acceleration_structure_build_sizes`, allocates both, then records
+/// `cmd_build_acceleration_structures` on a one-off command buffer (the same immediate-submit
+/// idiom `create_buffer_of_type`'s staging copy uses).
+fn build_acceleration_structure(
+    device: &ash::Device,
+    device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+    command_pool: vk::CommandPool,
+    queue: vk::Queue,
+    as_loader: &AccelerationStructure,
+    ty: vk::AccelerationStructureTypeKHR,
+    flags: vk::BuildAccelerationStructureFlagsKHR,
+    geometries: &[vk::AccelerationStructureGeometryKHR],
+    primitive_counts: &[u32],
+) -> AccelerationStructureHandle {
+    let mut build_info = vk::AccelerationStructureBuildGeometryInfoKHR {
+        ty,
+        flags,
+        mode: vk::BuildAccelerationStructureModeKHR::BUILD,
+        geometry_count: to_u32(geometries.len()),
+        p_geometries: geometries.as_ptr(),
+        ..Default::default()
+    };
+
+    let build_sizes = unsafe {
+        as_loader.get_acceleration_structure_build_sizes(
+            vk::AccelerationStructureBuildTypeKHR::DEVICE,
+            &build_info,
+            primitive_counts,
+        )
+    };
+
+    let usage = vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS
+        | vk::BufferUsageFlags::ACCELERATION_STRUCTURE_STORAGE_KHR;
+
+    let (buffer, memory) = unsafe {
+        create_buffer(
+            device,
+            device_mem_properties,
+            build_sizes.acceleration_structure_size,
+            usage,
+            vk::MemoryPropertyFlags::DEVICE_LOCAL,
+        )
+    };
+
+    let create_info = vk::AccelerationStructureCreateInfoKHR {
+        buffer,
+        size: build_sizes.acceleration_structure_size,
+        ty,
+        ..Default::default()
+    };
+
+    let inner = unsafe { as_loader.create_acceleration_structure(&create_info, None) }
+        .check_err("create acceleration structure");
+
+    let scratch_usage = vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS
+        | vk::BufferUsageFlags::STORAGE_BUFFER;
+
+    let (scratch_buffer, scratch_memory) = unsafe {
+        create_buffer(
+            device,
+            device_mem_properties,
+            build_sizes.build_scratch_size,
+            scratch_usage,
+            vk::MemoryPropertyFlags::DEVICE_LOCAL,
+        )
+    };
+
+    let scratch_address = buffer_device_address(device, scratch_buffer);
+
+    build_info.dst_acceleration_structure = inner;
+    build_info.scratch_data = vk::DeviceOrHostAddressKHR { device_address: scratch_address };
+
+    let range_info = vk::AccelerationStructureBuildRangeInfoKHR {
+        primitive_count: primitive_counts[0],
+        ..Default::default()
+    };
+    let range_infos: &[vk::AccelerationStructureBuildRangeInfoKHR] = &[range_info];
+
+    unsafe {
+        let cmd_buffer = start_single_command(device, command_pool);
+
+        as_loader.cmd_build_acceleration_structures(cmd_buffer, &[build_info], &[range_infos]);
+
+        end_single_command(device, command_pool, cmd_buffer, queue);
+
+        device.destroy_buffer(scratch_buffer, None);
+        device.free_memory(scratch_memory, None);
+    }
+
+    let address_info = vk::AccelerationStructureDeviceAddressInfoKHR {
+        acceleration_structure: inner,
+        ..Default::default()
+    };
+    let device_address =
+        unsafe { as_loader.get_acceleration_structure_device_address(&address_info) };
+
+    AccelerationStructureHandle {
+        device: device.clone(),
+        loader: as_loader.clone(),
+        inner,
+        device_address,
+        buffer,
+        memory,
+    }
+}
+
+/// A `vk::RAY_TRACING_KHR` pipeline built from raygen/miss/closest-hit SPIR-V, analogous to
+/// `Pipeline::new_compute` but with three shader stages/groups instead of one, plus the shader
+/// binding table `cmd_trace_rays` reads them from.
+pub struct RayTracePipeline {
+    device: ash::Device,
+    rt_loader: RayTracingPipeline,
+    pipeline: Pipeline,
+    sbt_buffer: vk::Buffer,
+    sbt_memory: vk::DeviceMemory,
+    raygen_region: vk::StridedDeviceAddressRegionKHR,
+    miss_region: vk::StridedDeviceAddressRegionKHR,
+    hit_region: vk::StridedDeviceAddressRegionKHR,
+}
+
+impl RayTracePipeline {
+    pub fn new(
+        device: &ash::Device,
+        device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+        rt_loader: RayTracingPipeline,
+        rt_properties: &vk::PhysicalDeviceRayTracingPipelinePropertiesKHR,
+        push_const_range: Option<&vk::PushConstantRange>,
+        desc_set_layout: vk::DescriptorSetLayout,
+        raygen_spirv: &[u8],
+        miss_spirv: &[u8],
+        closest_hit_spirv: &[u8],
+    ) -> Self {
+        let raygen = ShaderModule::new(device, raygen_spirv);
+        let miss = ShaderModule::new(device, miss_spirv);
+        let closest_hit = ShaderModule::new(device, closest_hit_spirv);
+
+        let stages = [
+            shader_stage_info(&raygen, vk::ShaderStageFlags::RAYGEN_KHR, None),
+            shader_stage_info(&miss, vk::ShaderStageFlags::MISS_KHR, None),
+            shader_stage_info(&closest_hit, vk::ShaderStageFlags::CLOSEST_HIT_KHR, None),
+        ];
+
+        let groups = [
+            general_group(0),
+            general_group(1),
+            triangles_hit_group(2),
+        ];
+
+        let layout = create_pipeline_layout(device, push_const_range, Some(&desc_set_layout));
+
+        let create_info = vk::RayTracingPipelineCreateInfoKHR {
+            stage_count: to_u32(stages.len()),
+            p_stages: stages.as_ptr(),
+            group_count: to_u32(groups.len()),
+            p_groups: groups.as_ptr(),
+            max_pipeline_ray_recursion_depth: 1,
+            layout,
+            ..Default::default()
+        };
+
+        let res = unsafe {
+            rt_loader.create_ray_tracing_pipelines(
+                vk::DeferredOperationKHR::null(),
+                vk::PipelineCache::null(),
+                &[create_info],
+                None,
+            )
+        };
+
+        let inner = match res {
+            Ok(pipelines) => pipelines[0],
+            Err((_pipelines, err)) => panic!("failed to create ray tracing pipeline: {err}"),
+        };
+
+        let pipeline = Pipeline::from_raw(device, inner, layout);
+
+        let sbt = build_shader_binding_table(
+            device,
+            device_mem_properties,
+            &rt_loader,
+            rt_properties,
+            inner,
+        );
+        let (sbt_buffer, sbt_memory, raygen_region, miss_region, hit_region) = sbt;
+
+        Self {
+            device: device.clone(),
+            rt_loader,
+            pipeline,
+            sbt_buffer,
+            sbt_memory,
+            raygen_region,
+            miss_region,
+            hit_region,
+        }
+    }
+
+    /// Binds this pipeline and `desc_set`, then traces `width` x `height` rays (one per output
+    /// pixel of the storage image bound at `desc_set`'s binding) into it.
+    pub fn trace(
+        &self,
+        cmd_buffer: vk::CommandBuffer,
+        desc_set: vk::DescriptorSet,
+        width: u32,
+        height: u32,
+    ) {
+        unsafe {
+            self.device.cmd_bind_pipeline(
+                cmd_buffer,
+                vk::PipelineBindPoint::RAY_TRACING_KHR,
+                self.pipeline.inner,
+            );
+
+            self.device.cmd_bind_descriptor_sets(
+                cmd_buffer,
+                vk::PipelineBindPoint::RAY_TRACING_KHR,
+                self.pipeline.layout,
+                0,
+                &[desc_set],
+                &[],
+            );
+
+            self.rt_loader.cmd_trace_rays(
+                cmd_buffer,
+                &self.raygen_region,
+                &self.miss_region,
+                &self.hit_region,
+                &vk::StridedDeviceAddressRegionKHR::default(),
+                width,
+                height,
+                1,
+            );
+        }
+    }
+}
+
+impl Drop for RayTracePipeline {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.destroy_buffer(self.sbt_buffer, None);
+            self.device.free_memory(self.sbt_memory, None);
+        }
+    }
+}
+
+fn general_group(index: u32) -> vk::RayTracingShaderGroupCreateInfoKHR {
+    vk::RayTracingShaderGroupCreateInfoKHR {
+        ty: vk::RayTracingShaderGroupTypeKHR::GENERAL,
+        general_shader: index,
+        closest_hit_shader: vk::SHADER_UNUSED_KHR,
+        any_hit_shader: vk::SHADER_UNUSED_KHR,
+        intersection_shader: vk::SHADER_UNUSED_KHR,
+        ..Default::default()
+    }
+}
+
+fn triangles_hit_group(closest_hit_index: u32) -> vk::RayTracingShaderGroupCreateInfoKHR {
+    vk::RayTracingShaderGroupCreateInfoKHR {
+        ty: vk::RayTracingShaderGroupTypeKHR::TRIANGLES_HIT_GROUP,
+        general_shader: vk::SHADER_UNUSED_KHR,
+        closest_hit_shader: closest_hit_index,
+        any_hit_shader: vk::SHADER_UNUSED_KHR,
+        intersection_shader: vk::SHADER_UNUSED_KHR,
+        ..Default::default()
+    }
+}
+
+/// Reads back the three shader groups' handles and packs them into one host-visible buffer, one
+/// region per group as `cmd_trace_rays` expects, each aligned to `shaderGroupHandleAlignment` (and
+/// sized up to `shaderGroupBaseAlignment` for the raygen region, which must be its own region of
+/// exactly one handle).
+fn build_shader_binding_table(
+    device: &ash::Device,
+    device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+    rt_loader: &RayTracingPipeline,
+    rt_properties: &vk::PhysicalDeviceRayTracingPipelinePropertiesKHR,
+    pipeline: vk::Pipeline,
+) -> (
+    vk::Buffer,
+    vk::DeviceMemory,
+    vk::StridedDeviceAddressRegionKHR,
+    vk::StridedDeviceAddressRegionKHR,
+    vk::StridedDeviceAddressRegionKHR,
+) {
+    let group_count = 3u32;
+    let handle_size = u64::from(rt_properties.shader_group_handle_size);
+    let handle_alignment = u64::from(rt_properties.shader_group_handle_alignment);
+    let base_alignment = u64::from(rt_properties.shader_group_base_alignment);
+
+    let aligned_handle_size = align_up(handle_size, handle_alignment);
+
+    let handles_size = (group_count as u64) * handle_size;
+    let handles = unsafe {
+        rt_loader
+            .get_ray_tracing_shader_group_handles(
+                pipeline,
+                0,
+                group_count,
+                usize_from_u64(handles_size),
+            )
+            .check_err("read shader group handles")
+    };
+
+    let raygen_size = align_up(aligned_handle_size, base_alignment);
+    let miss_size = align_up(aligned_handle_size, base_alignment);
+    let hit_size = align_up(aligned_handle_size, base_alignment);
+    let total_size = raygen_size + miss_size + hit_size;
+
+    let usage = vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS
+        | vk::BufferUsageFlags::SHADER_BINDING_TABLE_KHR
+        | vk::BufferUsageFlags::TRANSFER_DST;
+
+    let (buffer, memory) = unsafe {
+        create_buffer(
+            device,
+            device_mem_properties,
+            total_size,
+            usage,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        )
+    };
+
+    unsafe {
+        let mapping = device
+            .map_memory(memory, 0, total_size, vk::MemoryMapFlags::empty())
+            .check_err("map shader binding table")
+            .cast::<u8>();
+
+        for (i, offset) in [0u64, raygen_size, raygen_size + miss_size].into_iter().enumerate() {
+            let handle_size = usize_from_u64(handle_size);
+            let handle_offset = usize_from_u64(i as u64) * handle_size;
+            let src = handles[handle_offset..handle_offset + handle_size].as_ptr();
+
+            mapping.add(usize_from_u64(offset)).copy_from_nonoverlapping(src, handle_size);
+        }
+
+        device.unmap_memory(memory);
+    }
+
+    let address = buffer_device_address(device, buffer);
+
+    let region = |offset: u64, size: u64, stride: u64| vk::StridedDeviceAddressRegionKHR {
+        device_address: address + offset,
+        stride,
+        size,
+    };
+
+    let raygen_region = region(0, raygen_size, raygen_size);
+    let miss_region = region(raygen_size, miss_size, aligned_handle_size);
+    let hit_region = region(raygen_size + miss_size, hit_size, aligned_handle_size);
+
+    (buffer, memory, raygen_region, miss_region, hit_region)
+}
+
+const fn align_up(size: u64, alignment: u64) -> u64 {
+    if alignment == 0 {
+        size
+    } else {
+        (size + alignment - 1) & !(alignment - 1)
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn usize_from_u64(v: u64) -> usize {
+    v as usize
+}
@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+use std::ptr;
+use std::sync::Mutex;
+
 use ash::vk;
 
 use crate::utils::*;
@@ -12,17 +16,15 @@ pub struct SubpassBuilder {
     color_attachment: Option<vk::AttachmentReference>,
     depth_stencil_attachment: Option<vk::AttachmentReference>,
     input_attachments: Vec<vk::AttachmentReference>,
+    resolve_attachment: Option<vk::AttachmentReference>,
+    view_mask: u32,
 }
 
 pub struct DependencyBuilder(vk::SubpassDependency);
 
 impl RenderPassBuilder {
     pub fn new() -> Self {
-        Self {
-            attachments: vec![],
-            subpasses: vec![],
-            dependencies: vec![],
-        }
+        Self { attachments: vec![], subpasses: vec![], dependencies: vec![] }
     }
 
     pub fn with_attachment(
@@ -48,6 +50,73 @@ impl RenderPassBuilder {
         self
     }
 
+    /// Like `with_attachment`, but multisampled: `samples` above `TYPE_1` needs a
+    /// `SubpassBuilder::with_resolve_attachment` pointing at a single-sample attachment for the
+    /// subpass to resolve into, since a multisampled image can't be presented or sampled directly.
+    pub fn with_multisampled_attachment(
+        &mut self,
+        format: vk::Format,
+        samples: vk::SampleCountFlags,
+        load_op: vk::AttachmentLoadOp,
+        store_op: vk::AttachmentStoreOp,
+        final_layout: vk::ImageLayout,
+    ) -> &mut Self {
+        let attachment = vk::AttachmentDescription {
+            flags: vk::AttachmentDescriptionFlags::empty(),
+            format,
+            samples,
+            load_op,
+            store_op,
+            stencil_load_op: vk::AttachmentLoadOp::DONT_CARE,
+            stencil_store_op: vk::AttachmentStoreOp::DONT_CARE,
+            initial_layout: vk::ImageLayout::UNDEFINED,
+            final_layout,
+        };
+
+        self.attachments.push(attachment);
+        self
+    }
+
+    /// Like `with_attachment`, but with configurable stencil load/store ops instead of hardcoded
+    /// `DONT_CARE`, for stencil-buffer effects (outlines, portals, decals) that need stencil
+    /// content preserved or cleared across passes rather than left undefined.
+    ///
+    /// Status: this covers only the attachment-description side of a stencil buffer, i.e. a
+    /// single-sample one, or a multisampled one resolved "by hand" with an extra blit/copy pass.
+    /// It is NOT a depth/stencil *resolve* subpass: that needs
+    /// `vk::SubpassDescriptionDepthStencilResolve` chained into a subpass's `p_next`, which in turn
+    /// needs `build` migrated from `vk::RenderPassCreateInfo` to
+    /// `vk::RenderPassCreateInfo2`/`create_render_pass2`, gated on the `VK_KHR_create_renderpass2`
+    /// and `VK_KHR_depth_stencil_resolve` device extensions with a fallback to this legacy path on
+    /// older drivers. That's a bigger rework of this builder's internals than fits in one
+    /// attachment method, so it remains a follow-up, not something this method does on your
+    /// behalf.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_stencil_attachment(
+        &mut self,
+        format: vk::Format,
+        load_op: vk::AttachmentLoadOp,
+        store_op: vk::AttachmentStoreOp,
+        stencil_load_op: vk::AttachmentLoadOp,
+        stencil_store_op: vk::AttachmentStoreOp,
+        final_layout: vk::ImageLayout,
+    ) -> &mut Self {
+        let attachment = vk::AttachmentDescription {
+            flags: vk::AttachmentDescriptionFlags::empty(),
+            format,
+            samples: vk::SampleCountFlags::TYPE_1,
+            load_op,
+            store_op,
+            stencil_load_op,
+            stencil_store_op,
+            initial_layout: vk::ImageLayout::UNDEFINED,
+            final_layout,
+        };
+
+        self.attachments.push(attachment);
+        self
+    }
+
     pub fn with_subpass(&mut self, subpass: SubpassBuilder) -> &mut Self {
         self.subpasses.push(subpass);
         self
@@ -61,7 +130,36 @@ impl RenderPassBuilder {
     pub fn build(&mut self, device: &ash::Device) -> vk::RenderPass {
         let subpasses = self.subpasses.iter().map(SubpassBuilder::build).collect::<Vec<_>>();
 
+        let view_masks = self.subpasses.iter().map(|s| s.view_mask).collect::<Vec<_>>();
+        let any_nonzero = view_masks.iter().any(|&mask| mask != 0);
+        let all_nonzero = view_masks.iter().all(|&mask| mask != 0);
+        assert!(
+            view_masks.is_empty() || any_nonzero == all_nonzero,
+            "multiview requires every subpass to set a view mask via `with_view_mask`, or none \
+             of them to"
+        );
+
+        let correlation_masks = if any_nonzero {
+            vec![view_masks.iter().fold(0, |acc, mask| acc | mask)]
+        } else {
+            vec![]
+        };
+
+        let multiview_info = any_nonzero.then(|| vk::RenderPassMultiviewCreateInfo {
+            subpass_count: to_u32(view_masks.len()),
+            p_view_masks: view_masks.as_ptr(),
+            correlation_mask_count: to_u32(correlation_masks.len()),
+            p_correlation_masks: correlation_masks.as_ptr(),
+            ..Default::default()
+        });
+
+        let p_next = match &multiview_info {
+            Some(info) => ptr::addr_of!(*info).cast(),
+            None => ptr::null(),
+        };
+
         let create_info = vk::RenderPassCreateInfo {
+            p_next,
             attachment_count: to_u32(self.attachments.len()),
             p_attachments: self.attachments.as_ptr(),
             subpass_count: to_u32(self.subpasses.len()),
@@ -73,6 +171,16 @@ impl RenderPassBuilder {
 
         unsafe { device.create_render_pass(&create_info, None) }.check_err("create render pass")
     }
+
+    /// A hashable fingerprint of this builder's current attachments/subpasses/dependencies, for
+    /// `RenderPassCache` to recognize two builders that would produce an equivalent render pass.
+    fn key(&self) -> RenderPassKey {
+        RenderPassKey {
+            attachments: self.attachments.iter().map(AttachmentKey::from).collect(),
+            subpasses: self.subpasses.iter().map(SubpassKey::from).collect(),
+            dependencies: self.dependencies.iter().map(DependencyKey::from).collect(),
+        }
+    }
 }
 
 impl SubpassBuilder {
@@ -81,6 +189,8 @@ impl SubpassBuilder {
             color_attachment: None,
             depth_stencil_attachment: None,
             input_attachments: vec![],
+            resolve_attachment: None,
+            view_mask: 0,
         }
     }
 
@@ -106,10 +216,30 @@ impl SubpassBuilder {
         self
     }
 
+    /// Resolves this subpass's multisampled color attachment into `attachment` (a single-sample
+    /// attachment, e.g. the swapchain image) at the end of the subpass.
+    pub fn with_resolve_attachment(mut self, attachment: u32) -> Self {
+        let layout = vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL;
+        let attachment_ref = vk::AttachmentReference { attachment, layout };
+
+        self.resolve_attachment = Some(attachment_ref);
+        self
+    }
+
+    /// Opts this subpass into multiview: each bit broadcasts its draws to that layer of a
+    /// multi-layer framebuffer (e.g. `0b11` for a stereo left/right pair), per-layer divergence
+    /// coming from `gl_ViewIndex` in the shaders. `RenderPassBuilder::build` requires either every
+    /// subpass in the pass to set a mask, or none of them.
+    pub fn with_view_mask(mut self, view_mask: u32) -> Self {
+        self.view_mask = view_mask;
+        self
+    }
+
     pub fn build(&self) -> vk::SubpassDescription {
         let color_attachment_count = u32::from(self.color_attachment.is_some());
         let p_color_attachments = opt_to_ptr(&self.color_attachment);
         let p_depth_stencil_attachment = opt_to_ptr(&self.depth_stencil_attachment);
+        let p_resolve_attachments = opt_to_ptr(&self.resolve_attachment);
 
         vk::SubpassDescription {
             pipeline_bind_point: vk::PipelineBindPoint::GRAPHICS,
@@ -117,6 +247,7 @@ impl SubpassBuilder {
             p_input_attachments: self.input_attachments.as_ptr(),
             color_attachment_count,
             p_color_attachments,
+            p_resolve_attachments,
             p_depth_stencil_attachment,
             ..Default::default()
         }
@@ -151,6 +282,149 @@ impl DependencyBuilder {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct AttachmentKey {
+    format: vk::Format,
+    samples: vk::SampleCountFlags,
+    load_op: vk::AttachmentLoadOp,
+    store_op: vk::AttachmentStoreOp,
+    stencil_load_op: vk::AttachmentLoadOp,
+    stencil_store_op: vk::AttachmentStoreOp,
+    initial_layout: vk::ImageLayout,
+    final_layout: vk::ImageLayout,
+}
+
+impl From<&vk::AttachmentDescription> for AttachmentKey {
+    fn from(attachment: &vk::AttachmentDescription) -> Self {
+        Self {
+            format: attachment.format,
+            samples: attachment.samples,
+            load_op: attachment.load_op,
+            store_op: attachment.store_op,
+            stencil_load_op: attachment.stencil_load_op,
+            stencil_store_op: attachment.stencil_store_op,
+            initial_layout: attachment.initial_layout,
+            final_layout: attachment.final_layout,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct AttachmentRefKey {
+    attachment: u32,
+    layout: vk::ImageLayout,
+}
+
+impl From<&vk::AttachmentReference> for AttachmentRefKey {
+    fn from(reference: &vk::AttachmentReference) -> Self {
+        Self { attachment: reference.attachment, layout: reference.layout }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct SubpassKey {
+    color_attachment: Option<AttachmentRefKey>,
+    depth_stencil_attachment: Option<AttachmentRefKey>,
+    input_attachments: Vec<AttachmentRefKey>,
+    resolve_attachment: Option<AttachmentRefKey>,
+    view_mask: u32,
+}
+
+impl From<&SubpassBuilder> for SubpassKey {
+    fn from(subpass: &SubpassBuilder) -> Self {
+        Self {
+            color_attachment: subpass.color_attachment.as_ref().map(AttachmentRefKey::from),
+            depth_stencil_attachment: subpass
+                .depth_stencil_attachment
+                .as_ref()
+                .map(AttachmentRefKey::from),
+            input_attachments: subpass
+                .input_attachments
+                .iter()
+                .map(AttachmentRefKey::from)
+                .collect(),
+            resolve_attachment: subpass.resolve_attachment.as_ref().map(AttachmentRefKey::from),
+            view_mask: subpass.view_mask,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct DependencyKey {
+    src_subpass: u32,
+    dst_subpass: u32,
+    src_stage_mask: vk::PipelineStageFlags,
+    dst_stage_mask: vk::PipelineStageFlags,
+    src_access_mask: vk::AccessFlags,
+    dst_access_mask: vk::AccessFlags,
+    dependency_flags: vk::DependencyFlags,
+}
+
+impl From<&vk::SubpassDependency> for DependencyKey {
+    fn from(dependency: &vk::SubpassDependency) -> Self {
+        Self {
+            src_subpass: dependency.src_subpass,
+            dst_subpass: dependency.dst_subpass,
+            src_stage_mask: dependency.src_stage_mask,
+            dst_stage_mask: dependency.dst_stage_mask,
+            src_access_mask: dependency.src_access_mask,
+            dst_access_mask: dependency.dst_access_mask,
+            dependency_flags: dependency.dependency_flags,
+        }
+    }
+}
+
+/// A hashable fingerprint of everything `RenderPassBuilder::build` feeds into
+/// `vk::RenderPassCreateInfo`. `vk::AttachmentDescription`/`vk::AttachmentReference`/
+/// `vk::SubpassDependency` are plain data (no pointers) but aren't `Hash`/`Eq`, so each is mirrored
+/// field-for-field here instead.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct RenderPassKey {
+    attachments: Vec<AttachmentKey>,
+    subpasses: Vec<SubpassKey>,
+    dependencies: Vec<DependencyKey>,
+}
+
+/// Deduplicates `vk::RenderPass` creation: repeated `RenderPassBuilder`s describing the same
+/// attachments/subpasses/dependencies (e.g. the same kind of pass built for several
+/// differently-sized offscreen targets) share one handle through `get_or_create` instead of each
+/// call minting its own, and this is the single place that destroys them all on teardown.
+pub struct RenderPassCache {
+    device: ash::Device,
+    passes: Mutex<HashMap<RenderPassKey, vk::RenderPass>>,
+}
+
+impl RenderPassCache {
+    pub fn new(device: ash::Device) -> Self {
+        Self { device, passes: Mutex::new(HashMap::new()) }
+    }
+
+    /// Returns the cached `vk::RenderPass` matching `builder`'s current configuration, building
+    /// and caching a new one the first time that configuration is seen.
+    pub fn get_or_create(&self, builder: &mut RenderPassBuilder) -> vk::RenderPass {
+        let key = builder.key();
+        let mut passes = self.passes.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(&pass) = passes.get(&key) {
+            return pass;
+        }
+
+        let pass = builder.build(&self.device);
+        passes.insert(key, pass);
+        pass
+    }
+
+    /// Destroys every cached render pass. Must run before the owning `ash::Device` is destroyed,
+    /// the same as any other Vulkan object this renderer owns.
+    pub unsafe fn destroy(&self) {
+        let mut passes = self.passes.lock().unwrap_or_else(|e| e.into_inner());
+
+        for (_, pass) in passes.drain() {
+            self.device.destroy_render_pass(pass, None);
+        }
+    }
+}
+
 pub fn create_render_pass_with_attachments(
     device: &ash::Device,
     swapchain_format: vk::Format,
@@ -232,13 +506,64 @@ pub fn create_render_pass_with_attachments(
         .build(device)
 }
 
+/// `view_mask` opts into multiview: each bit is a layer of a 2D-array framebuffer that the
+/// subpass broadcasts the same draws to (e.g. `0b11` for a stereo left/right pair), per-layer
+/// divergence coming from `gl_ViewIndex` in the shaders. Passing `None` keeps the existing
+/// single-view behavior.
+///
+/// The supporting pieces are all in place: `Texture::new_render_target`/`FramebufferAttachment::
+/// new` take an `array_layers` count for the 2-layer attachments a stereo pass renders into,
+/// `create_logical_device` chains `VkPhysicalDeviceMultiviewFeatures` into device creation when
+/// `VK_KHR_multiview` is available, `SubpassBuilder::with_view_mask` chains the
+/// `vk::RenderPassMultiviewCreateInfo` this subpass needs, and `UniformBufferType::StereoCamera`
+/// holds both eyes' view/proj pairs for a shader to index by `gl_ViewIndex`. Still missing: an
+/// actual stereo-capable scene pass (a second camera eye, matching `.vert`/`.frag` shaders reading
+/// `gl_ViewIndex`, and a presentation path that composites or selects between the two resulting
+/// layers) wired into `Renderer`'s main draw loop — that's a structural change to the primary
+/// render path this engine has no VR/stereo output target to drive yet, so `None` remains the only
+/// value any call site passes today.
+///
+/// `samples` above `vk::SampleCountFlags::TYPE_1` renders into a multisampled color+depth pair
+/// instead and resolves the color attachment into the single-sample swapchain image at the end of
+/// the subpass, via `SubpassBuilder::with_resolve_attachment`. Pass `None` to keep the existing
+/// non-MSAA single-sample pass.
 pub fn create_render_pass_no_attachments(
     device: &ash::Device,
     swapchain_format: vk::Format,
     depth_format: vk::Format,
+    view_mask: Option<u32>,
+    samples: Option<vk::SampleCountFlags>,
 ) -> vk::RenderPass {
-    RenderPassBuilder::new()
+    let mut pass = RenderPassBuilder::new();
+
+    let mut subpass = if let Some(samples) = samples {
+        pass.with_multisampled_attachment(
+            swapchain_format,
+            samples,
+            vk::AttachmentLoadOp::CLEAR,
+            vk::AttachmentStoreOp::DONT_CARE,
+            vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+        )
+        .with_multisampled_attachment(
+            depth_format,
+            samples,
+            vk::AttachmentLoadOp::CLEAR,
+            vk::AttachmentStoreOp::DONT_CARE,
+            vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+        )
         .with_attachment(
+            swapchain_format,
+            vk::AttachmentLoadOp::DONT_CARE,
+            vk::AttachmentStoreOp::STORE,
+            vk::ImageLayout::PRESENT_SRC_KHR,
+        );
+
+        SubpassBuilder::new()
+            .with_color_attachment(0)
+            .with_depth_attachment(1)
+            .with_resolve_attachment(2)
+    } else {
+        pass.with_attachment(
             swapchain_format,
             vk::AttachmentLoadOp::CLEAR,
             vk::AttachmentStoreOp::STORE,
@@ -249,8 +574,16 @@ pub fn create_render_pass_no_attachments(
             vk::AttachmentLoadOp::CLEAR,
             vk::AttachmentStoreOp::DONT_CARE,
             vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
-        )
-        .with_subpass(SubpassBuilder::new().with_color_attachment(0).with_depth_attachment(1))
+        );
+
+        SubpassBuilder::new().with_color_attachment(0).with_depth_attachment(1)
+    };
+
+    if let Some(view_mask) = view_mask {
+        subpass = subpass.with_view_mask(view_mask);
+    }
+
+    pass.with_subpass(subpass)
         .with_dependency(
             DependencyBuilder::new()
                 .subpasses(vk::SUBPASS_EXTERNAL, 0)
@@ -0,0 +1,125 @@
+use std::mem::size_of;
+
+use ash::vk;
+
+use super::vulkan::*;
+
+/// One sub-range handed out by `UploadPool::alloc`: `offset` into the current frame's buffer
+/// (already aligned to `min_uniform_buffer_offset_alignment`), ready to use as a dynamic
+/// descriptor offset once a caller binds through it.
+pub struct UploadAllocation {
+    pub offset: u64,
+    pub size: u64,
+}
+
+/// A per-frame-in-flight bump allocator over one large host-visible buffer, so many meshes can
+/// suballocate their uniform/instance data out of a single coherent buffer each frame instead of
+/// each owning its own small mapped allocation. `reset` rewinds the bump pointer to the start of
+/// the current frame's buffer; every `alloc` after that hands out the next correctly aligned
+/// sub-range until `reset` is called again for that frame.
+///
+/// This is a self-contained building block, not yet wired into `MeshData`/`UniformBuffer`: doing
+/// so means switching their descriptor bindings from `UNIFORM_BUFFER` to `UNIFORM_BUFFER_DYNAMIC`
+/// and threading a per-draw dynamic offset through `MeshData::record_draw_commands`'s
+/// `cmd_bind_descriptor_sets` call, which is a larger rework of `mesh.rs`'s descriptor-set
+/// plumbing than fits in this change; that wiring is left as a follow-up.
+pub struct UploadPool {
+    device: ash::Device,
+    buffers: Vec<vk::Buffer>,
+    memories: Vec<vk::DeviceMemory>,
+    mappings: Vec<*mut u8>,
+    capacity: u64,
+    alignment: u64,
+    cursor: u64,
+}
+
+impl UploadPool {
+    pub fn new(
+        device: &ash::Device,
+        device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+        device_properties: &vk::PhysicalDeviceProperties,
+        capacity: u64,
+        copies: usize,
+    ) -> Self {
+        let usage = vk::BufferUsageFlags::UNIFORM_BUFFER | vk::BufferUsageFlags::VERTEX_BUFFER;
+        let (buffers, memories, mappings) = create_host_visible_shader_buffers::<u8>(
+            device,
+            device_mem_properties,
+            usage,
+            capacity,
+            copies,
+            None,
+            "upload pool",
+        );
+
+        Self {
+            device: device.clone(),
+            buffers,
+            memories,
+            mappings,
+            capacity,
+            alignment: device_properties.limits.min_uniform_buffer_offset_alignment,
+            cursor: 0,
+        }
+    }
+
+    /// Rewinds the bump pointer for a new frame. Must be called once per frame, before any
+    /// `alloc` calls for that frame, since every allocation handed out last time this frame's
+    /// buffer was used is now free to be overwritten.
+    pub fn reset(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Copies `data` into `current_frame`'s buffer at the next aligned offset and returns that
+    /// sub-range, or `None` if the pool's `capacity` is exhausted for this frame.
+    pub fn alloc<T: Copy>(&mut self, current_frame: usize, data: &T) -> Option<UploadAllocation> {
+        let size = size_of::<T>() as u64;
+        let offset = align_up(self.cursor, self.alignment);
+
+        if offset + size > self.capacity {
+            return None;
+        }
+
+        unsafe {
+            let dst = self.mappings[current_frame].add(offset_usize(offset));
+            let src = std::ptr::from_ref(data).cast::<u8>();
+
+            std::ptr::copy_nonoverlapping(src, dst, offset_usize(size));
+        }
+
+        self.cursor = offset + size;
+
+        Some(UploadAllocation { offset, size })
+    }
+
+    pub fn buffer(&self, current_frame: usize) -> vk::Buffer {
+        self.buffers[current_frame]
+    }
+}
+
+impl Drop for UploadPool {
+    fn drop(&mut self) {
+        unsafe {
+            for buf in &self.buffers {
+                self.device.destroy_buffer(*buf, None);
+            }
+
+            for mem in &self.memories {
+                self.device.free_memory(*mem, None);
+            }
+        }
+    }
+}
+
+const fn align_up(value: u64, alignment: u64) -> u64 {
+    if alignment == 0 {
+        return value;
+    }
+
+    (value + alignment - 1) / alignment * alignment
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn offset_usize(offset: u64) -> usize {
+    offset as usize
+}
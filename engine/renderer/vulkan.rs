@@ -1,9 +1,9 @@
-use std::ffi::{c_void, CStr, CString};
-use std::mem::size_of_val;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::mem::{size_of, size_of_val};
 use std::ptr;
 
 use anyhow::Result;
-use ash::extensions::ext::DebugUtils;
+use ash::extensions::ext::{DebugReport, DebugUtils};
 use ash::extensions::khr::{Surface as VkSurface, Swapchain as VkSwapchain};
 use ash::vk;
 use log::{debug, error, info, warn};
@@ -30,6 +30,11 @@ const REQ_DEVICE_EXTENSIONS: &[&str] = &[
     "VK_KHR_shader_non_semantic_info",
 ];
 
+/// Extensions that unlock optional rendering paths but aren't required to run at all, e.g.
+/// `VK_KHR_shared_presentable_image` for the opt-in low-latency shared-present swapchain mode, or
+/// `VK_KHR_multiview` for `SubpassBuilder::with_view_mask`'s stereo/VR rendering path.
+const OPT_DEVICE_EXTENSIONS: &[&str] = &["VK_KHR_shared_presentable_image", "VK_KHR_multiview"];
+
 pub const BASE_SUBRESOURCE_RANGE: vk::ImageSubresourceRange = vk::ImageSubresourceRange {
     aspect_mask: vk::ImageAspectFlags::COLOR,
     base_mip_level: 0,
@@ -69,7 +74,15 @@ pub struct Swapchain {
     pub handle: vk::SwapchainKHR,
     pub format: vk::SurfaceFormatKHR,
     pub extent: vk::Extent2D,
+    pub images: Vec<vk::Image>,
     pub image_views: Vec<vk::ImageView>,
+    acquisition_semaphores: Vec<vk::Semaphore>,
+    acquisition_idx: u32,
+}
+
+pub enum AcquireResult {
+    Image { index: u32, semaphore: vk::Semaphore },
+    NeedsRecreation,
 }
 
 pub struct Texture {
@@ -104,11 +117,53 @@ pub struct Queues {
     pub present: vk::Queue,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct PhysDeviceInfo {
     pub phys_device: vk::PhysicalDevice,
-    properties: vk::PhysicalDeviceProperties,
+    pub properties: vk::PhysicalDeviceProperties,
     pub queue_family_indices: QueueFamilyIndices,
+    /// Nanoseconds per timestamp tick, needed to interpret `GpuTimer::resolve()` results.
+    pub timestamp_period: f32,
+    pub gpu_info: GpuInfo,
+    /// The requested features this device was confirmed to support, so logical-device creation
+    /// can enable exactly what was requested-and-available.
+    pub satisfied_features: vk::PhysicalDeviceFeatures,
+    /// Which of `DeviceRequirements::optional_extensions` this device supports.
+    pub available_optional_extensions: Vec<&'static str>,
+    pub device_local_vram: u64,
+}
+
+/// What a physical device must (required) and should (optional) support. `pick_phys_device`
+/// rejects candidates missing a required feature/extension, then ranks the rest by device type,
+/// number of optional extensions present, and total `DEVICE_LOCAL` VRAM.
+#[derive(Clone, Copy, Default)]
+pub struct DeviceRequirements {
+    pub required_features: vk::PhysicalDeviceFeatures,
+    pub required_extensions: &'static [&'static str],
+    pub optional_extensions: &'static [&'static str],
+}
+
+impl DeviceRequirements {
+    pub fn default_for_game() -> Self {
+        Self {
+            required_features: vk::PhysicalDeviceFeatures {
+                // fill_mode_non_solid: 1,
+                shader_clip_distance: vk::TRUE,
+                ..Default::default()
+            },
+            required_extensions: REQ_DEVICE_EXTENSIONS,
+            optional_extensions: OPT_DEVICE_EXTENSIONS,
+        }
+    }
+}
+
+/// Compute dispatch limits, used to pick workgroup dimensions instead of hardcoding a tile size.
+#[derive(Clone, Copy)]
+pub struct GpuInfo {
+    pub subgroup_size: u32,
+    pub max_compute_work_group_size: [u32; 3],
+    pub max_compute_work_group_invocations: u32,
+    pub max_compute_work_group_count: [u32; 3],
 }
 
 #[derive(Default)]
@@ -138,6 +193,7 @@ impl Drop for Surface {
 }
 
 impl Swapchain {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         phys_device: vk::PhysicalDevice,
         surface: &Surface,
@@ -147,6 +203,7 @@ impl Swapchain {
         instance: &ash::Instance,
         device: &ash::Device,
         queue_family_indices: &QueueFamilyIndices,
+        debug_data: Option<&DebugData>,
     ) -> Self {
         let surface_capabilities = get_surface_capabilities(phys_device, surface);
         let extent = choose_swapchain_extent(win_width, win_height, &surface_capabilities);
@@ -164,16 +221,107 @@ impl Swapchain {
         let images = get_swapchain_images(&loader, handle);
         let image_views = create_swapchain_image_views(device, swapchain_format.format, &images);
 
+        // One semaphore per swapchain image, not per frame-in-flight: there can never be more
+        // outstanding acquires than images, so round-robining over this pool guarantees we never
+        // hand `vkAcquireNextImageKHR` a semaphore that's still pending on a previous acquire.
+        let acquisition_semaphores =
+            create_semaphores(device, image_views.len(), debug_data, "acquisition semaphore");
+
+        for (i, &image_view) in image_views.iter().enumerate() {
+            set_name_opt(debug_data, device, image_view, &format!("swapchain image view {i}"));
+        }
+
         Self {
             device: device.clone(),
             format: swapchain_format,
             loader,
             extent,
             handle,
+            images,
             image_views,
+            acquisition_semaphores,
+            acquisition_idx: 0,
         }
     }
 
+    /// Acquires the next swapchain image, signalling the returned semaphore once it's ready.
+    ///
+    /// The semaphore is picked by `acquisition_idx % images.len()` rather than by image index,
+    /// since the image index isn't known until after the acquire call returns.
+    pub fn acquire_next_image(&mut self) -> AcquireResult {
+        let semaphore =
+            self.acquisition_semaphores[self.acquisition_idx as usize % self.acquisition_semaphores.len()];
+
+        let res = unsafe {
+            self.loader.acquire_next_image(self.handle, DRAW_TIMEOUT_NS, semaphore, vk::Fence::null())
+        };
+
+        match res {
+            Ok((_, true)) | Err(vk::Result::ERROR_OUT_OF_DATE_KHR | vk::Result::SUBOPTIMAL_KHR) => {
+                AcquireResult::NeedsRecreation
+            }
+            Ok((index, false)) => {
+                self.acquisition_idx = self.acquisition_idx.wrapping_add(1);
+                AcquireResult::Image { index, semaphore }
+            }
+            Err(e) => panic!("failed to acquire next image: err = {}", e),
+        }
+    }
+
+    /// Rebuilds the swapchain in place after a resize or `VK_ERROR_OUT_OF_DATE_KHR`, keeping the
+    /// loader and chosen surface format.
+    pub fn recreate(
+        &mut self,
+        phys_device: vk::PhysicalDevice,
+        surface: &Surface,
+        win_width: u32,
+        win_height: u32,
+        queue_family_indices: &QueueFamilyIndices,
+        debug_data: Option<&DebugData>,
+    ) {
+        unsafe {
+            self.device.device_wait_idle().check_err("wait for device");
+
+            for image_view in &self.image_views {
+                self.device.destroy_image_view(*image_view, None);
+            }
+
+            self.loader.destroy_swapchain(self.handle, None);
+        }
+
+        let surface_capabilities = get_surface_capabilities(phys_device, surface);
+        self.extent = choose_swapchain_extent(win_width, win_height, &surface_capabilities);
+        let present_mode = choose_swapchain_present_mode(phys_device, surface);
+
+        self.handle = create_swapchain(
+            surface,
+            present_mode,
+            &surface_capabilities,
+            self.format,
+            self.extent,
+            &self.loader,
+            queue_family_indices,
+        );
+
+        let images = get_swapchain_images(&self.loader, self.handle);
+        self.image_views = create_swapchain_image_views(&self.device, self.format.format, &images);
+        self.images = images;
+
+        unsafe {
+            for sem in self.acquisition_semaphores.drain(..) {
+                self.device.destroy_semaphore(sem, None);
+            }
+        }
+
+        self.acquisition_semaphores = create_semaphores(
+            &self.device,
+            self.image_views.len(),
+            debug_data,
+            "acquisition semaphore",
+        );
+        self.acquisition_idx = 0;
+    }
+
     pub fn present(
         &self,
         wait_semaphore: vk::Semaphore,
@@ -189,31 +337,74 @@ impl Swapchain {
             ..Default::default()
         };
 
+        // Surface OUT_OF_DATE/SUBOPTIMAL to the caller rather than swallowing it: both mean the
+        // caller should recreate the swapchain before the next acquire.
         unsafe { self.loader.queue_present(present, &present_info) }
     }
 
+    /// For a `SHARED_DEMAND_REFRESH` swapchain: tells the driver the persistently-acquired image
+    /// was written to and should be read again. Re-presents with no wait semaphore, since the
+    /// caller is expected to have already synchronized its writes before calling this.
+    pub fn signal_present(&self, image_index: u32, present: vk::Queue) -> Result<bool, vk::Result> {
+        let present_info = vk::PresentInfoKHR {
+            swapchain_count: 1,
+            p_swapchains: &self.handle,
+            p_image_indices: &image_index,
+            ..Default::default()
+        };
+
+        unsafe { self.loader.queue_present(present, &present_info) }
+    }
+
+    /// For a `SHARED_*_REFRESH` swapchain: whether the shared image is still usable, mirroring
+    /// the `OUT_OF_DATE`/`SUBOPTIMAL` semantics of `acquire_next_image` and `present`.
+    pub fn status(&self) -> Result<bool, vk::Result> {
+        unsafe { self.loader.get_swapchain_status(self.handle) }
+    }
+
     pub unsafe fn destroy(&mut self) {
         for image_view in &self.image_views {
             self.device.destroy_image_view(*image_view, None);
         }
 
+        for sem in &self.acquisition_semaphores {
+            self.device.destroy_semaphore(*sem, None);
+        }
+
         self.loader.destroy_swapchain(self.handle, None);
     }
 }
 
 impl Texture {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
+        instance: &ash::Instance,
+        phys_device: vk::PhysicalDevice,
         device: &ash::Device,
         device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
         command_pool: vk::CommandPool,
         queue: vk::Queue,
         path: &'static str,
+        debug_data: Option<&DebugData>,
+        name: &str,
     ) -> Self {
-        let (image, memory, layout) =
-            create_texture_image(device, device_mem_properties, command_pool, queue, path);
+        let (image, memory, layout, mip_levels) = create_texture_image(
+            instance,
+            phys_device,
+            device,
+            device_mem_properties,
+            command_pool,
+            queue,
+            path,
+        );
         let format = vk::Format::R8G8B8A8_SRGB;
-        let image_view = create_image_view(device, image, format, vk::ImageAspectFlags::COLOR, 1);
-        let sampler = create_texture_sampler(device);
+        let image_view =
+            create_image_view(device, image, format, vk::ImageAspectFlags::COLOR, mip_levels);
+        let sampler = create_texture_sampler(device, mip_levels);
+
+        set_name_opt(debug_data, device, image, &format!("{name} image"));
+        set_name_opt(debug_data, device, image_view, &format!("{name} image view"));
+        set_name_opt(debug_data, device, sampler, &format!("{name} sampler"));
 
         Self {
             device: device.clone(),
@@ -226,6 +417,7 @@ impl Texture {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new_compute(
         instance: &ash::Instance,
         phys_device: vk::PhysicalDevice,
@@ -236,6 +428,8 @@ impl Texture {
         queue: vk::Queue,
         width: u32,
         height: u32,
+        debug_data: Option<&DebugData>,
+        name: &str,
     ) -> Self {
         let format = find_supported_format(
             instance,
@@ -251,7 +445,17 @@ impl Texture {
             | vk::ImageUsageFlags::STORAGE
             | vk::ImageUsageFlags::SAMPLED;
         let (image, memory) =
-            create_image(device, device_mem_properties, format, width, height, usage);
+            create_image(
+                device,
+                device_mem_properties,
+                format,
+                width,
+                height,
+                1,
+                1,
+                vk::ImageCreateFlags::empty(),
+                usage,
+            );
 
         // Must be GENERAL because of STORAGE_IMAGE
         let layout = vk::ImageLayout::GENERAL;
@@ -261,12 +465,137 @@ impl Texture {
             command_pool,
             queue,
             image,
+            BASE_SUBRESOURCE_RANGE,
             vk::ImageLayout::UNDEFINED,
             layout,
         );
 
         let image_view = create_image_view(device, image, format, vk::ImageAspectFlags::COLOR, 1);
-        let sampler = create_texture_sampler(device);
+        let sampler = create_texture_sampler(device, 1);
+
+        set_name_opt(debug_data, device, image, &format!("{name} image"));
+        set_name_opt(debug_data, device, image_view, &format!("{name} image view"));
+        set_name_opt(debug_data, device, sampler, &format!("{name} sampler"));
+
+        Self {
+            device: device.clone(),
+            image,
+            memory,
+            image_view,
+            sampler,
+            layout,
+            format,
+        }
+    }
+
+    /// A sampled target populated by `cmd_copy_image` rather than a render pass (unlike
+    /// `new_render_target`, this has no `COLOR_ATTACHMENT` usage), for `Renderer`'s per-frame scene
+    /// capture: a copy of the just-rendered swapchain image that the post-processing chain samples
+    /// as its `Original`/first `Source` input. Rests at `SHADER_READ_ONLY_OPTIMAL` between frames;
+    /// the caller transitions it to `TRANSFER_DST_OPTIMAL` before each copy and back afterwards.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_capture(
+        device: &ash::Device,
+        device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+        command_pool: vk::CommandPool,
+        queue: vk::Queue,
+        format: vk::Format,
+        width: u32,
+        height: u32,
+        debug_data: Option<&DebugData>,
+        name: &str,
+    ) -> Self {
+        let usage = vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED;
+        let (image, memory) = create_image(
+            device,
+            device_mem_properties,
+            format,
+            width,
+            height,
+            1,
+            1,
+            vk::ImageCreateFlags::empty(),
+            usage,
+        );
+
+        let layout = vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+
+        transition_image_layout(
+            device,
+            command_pool,
+            queue,
+            image,
+            BASE_SUBRESOURCE_RANGE,
+            vk::ImageLayout::UNDEFINED,
+            layout,
+        );
+
+        let image_view = create_image_view(device, image, format, vk::ImageAspectFlags::COLOR, 1);
+        let sampler = create_texture_sampler(device, 1);
+
+        set_name_opt(debug_data, device, image, &format!("{name} image"));
+        set_name_opt(debug_data, device, image_view, &format!("{name} image view"));
+        set_name_opt(debug_data, device, sampler, &format!("{name} sampler"));
+
+        Self {
+            device: device.clone(),
+            image,
+            memory,
+            image_view,
+            sampler,
+            layout,
+            format,
+        }
+    }
+
+    /// A color attachment meant to be rendered into by one subpass and sampled by another (e.g. a
+    /// post-processing pass's output). Left in `UNDEFINED` here; the owning render pass's
+    /// attachment description transitions it to `SHADER_READ_ONLY_OPTIMAL` once the pass storing
+    /// into it completes, the same way swapchain/depth attachments are never pre-transitioned.
+    ///
+    /// `array_layers` above 1 makes this a multiview-ready target (e.g. `2` for a stereo pair): the
+    /// image is allocated with that many layers and the view covers all of them, for a subpass
+    /// with `SubpassBuilder::with_view_mask` to broadcast its draws across.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_render_target(
+        device: &ash::Device,
+        device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+        format: vk::Format,
+        width: u32,
+        height: u32,
+        array_layers: u32,
+        filter: vk::Filter,
+        debug_data: Option<&DebugData>,
+        name: &str,
+    ) -> Self {
+        let usage = vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::SAMPLED;
+        let (image, memory) = create_image(
+            device,
+            device_mem_properties,
+            format,
+            width,
+            height,
+            1,
+            array_layers,
+            vk::ImageCreateFlags::empty(),
+            usage,
+        );
+
+        let layout = vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+
+        let image_view = create_image_view_layers(
+            device,
+            image,
+            format,
+            vk::ImageAspectFlags::COLOR,
+            1,
+            array_layers,
+        );
+        let sampler = create_sampler(device, filter, 1);
+
+        set_name_opt(debug_data, device, image, &format!("{name} image"));
+        set_name_opt(debug_data, device, image_view, &format!("{name} image view"));
+        set_name_opt(debug_data, device, sampler, &format!("{name} sampler"));
 
         Self {
             device: device.clone(),
@@ -292,22 +621,41 @@ impl Drop for Texture {
 }
 
 impl FramebufferAttachment {
+    /// `array_layers` above 1 makes this a multiview-ready attachment (e.g. `2` for a stereo
+    /// pair); see `Texture::new_render_target`'s doc comment for the same knob on a sampled target.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         device: &ash::Device,
         device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
         extent: vk::Extent2D,
         format: vk::Format,
+        array_layers: u32,
         usage: vk::ImageUsageFlags,
         aspect_mask: vk::ImageAspectFlags,
+        debug_data: Option<&DebugData>,
+        name: &str,
     ) -> Self {
         if !usage.contains(vk::ImageUsageFlags::INPUT_ATTACHMENT) {
             warn!("FramebufferAttachment has no INPUT_ATTACHMENT usage");
         }
 
-        let (image, memory) =
-            create_image(device, device_mem_properties, format, extent.width, extent.height, usage);
+        let (image, memory) = create_image(
+            device,
+            device_mem_properties,
+            format,
+            extent.width,
+            extent.height,
+            1,
+            array_layers,
+            vk::ImageCreateFlags::empty(),
+            usage,
+        );
+
+        let image_view =
+            create_image_view_layers(device, image, format, aspect_mask, 1, array_layers);
 
-        let image_view = create_image_view(device, image, format, aspect_mask, 1);
+        set_name_opt(debug_data, device, image, &format!("{name} image"));
+        set_name_opt(debug_data, device, image_view, &format!("{name} image view"));
 
         Self {
             device: device.clone(),
@@ -375,7 +723,11 @@ pub fn create_instance(app_name: &str, entry: &ash::Entry, window: &Window) -> a
     };
 
     if cfg!(debug_assertions) {
-        req_inst_exts_cptrs.push(vk::ExtDebugUtilsFn::name().as_ptr());
+        if instance_extension_supported(entry, vk::ExtDebugUtilsFn::name()) {
+            req_inst_exts_cptrs.push(vk::ExtDebugUtilsFn::name().as_ptr());
+        } else {
+            req_inst_exts_cptrs.push(vk::ExtDebugReportFn::name().as_ptr());
+        }
     }
 
     print_instance_extensions(entry, &req_inst_exts_cptrs);
@@ -455,6 +807,16 @@ fn get_validation_layers(entry: &ash::Entry) -> Vec<&str> {
     layers
 }
 
+fn instance_extension_supported(entry: &ash::Entry, name: &CStr) -> bool {
+    let extensions = entry
+        .enumerate_instance_extension_properties(None)
+        .check_err("enumerate instance extensions");
+
+    extensions
+        .iter()
+        .any(|ext| unsafe { CStr::from_ptr(ext.extension_name.as_ptr()) } == name)
+}
+
 fn print_instance_extensions(entry: &ash::Entry, req_inst_exts_cptrs: &[*const i8]) {
     print_textual_items("Required instance extensions", req_inst_exts_cptrs, |x| *x);
 
@@ -474,6 +836,14 @@ pub fn create_debug_data(entry: &ash::Entry, instance: &ash::Instance) -> Option
         return None;
     }
 
+    if instance_extension_supported(entry, vk::ExtDebugUtilsFn::name()) {
+        Some(create_debug_utils_messenger(entry, instance))
+    } else {
+        Some(create_debug_report_callback_ext(entry, instance))
+    }
+}
+
+fn create_debug_utils_messenger(entry: &ash::Entry, instance: &ash::Instance) -> DebugData {
     let message_severity = vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE
         | vk::DebugUtilsMessageSeverityFlagsEXT::INFO
         | vk::DebugUtilsMessageSeverityFlagsEXT::WARNING
@@ -496,12 +866,77 @@ pub fn create_debug_data(entry: &ash::Entry, instance: &ash::Instance) -> Option
         unsafe { debug_utils_loader.create_debug_utils_messenger(&debug_info, None) }
             .check_err("create debug messenger");
 
-    let data = DebugData {
-        debug_utils_loader,
-        debug_messenger,
+    DebugData::Utils { debug_utils_loader, debug_messenger }
+}
+
+/// Fallback for drivers that don't expose `VK_EXT_debug_utils`, namely older drivers and some
+/// mobile/portability stacks reachable via `VK_KHR_portability_subset`.
+fn create_debug_report_callback_ext(entry: &ash::Entry, instance: &ash::Instance) -> DebugData {
+    let flags = vk::DebugReportFlagsEXT::ERROR
+        | vk::DebugReportFlagsEXT::WARNING
+        | vk::DebugReportFlagsEXT::PERFORMANCE_WARNING
+        | vk::DebugReportFlagsEXT::INFORMATION
+        | vk::DebugReportFlagsEXT::DEBUG;
+
+    let debug_info = vk::DebugReportCallbackCreateInfoEXT {
+        flags,
+        pfn_callback: Some(debug_report_callback),
+        ..Default::default()
     };
 
-    Some(data)
+    let debug_report_loader = DebugReport::new(entry, instance);
+
+    let debug_report_callback =
+        unsafe { debug_report_loader.create_debug_report_callback(&debug_info, None) }
+            .check_err("create debug report callback");
+
+    DebugData::Report { debug_report_loader, debug_report_callback }
+}
+
+impl DebugData {
+    /// Tags a Vulkan handle with a human-readable name so validation messages print it instead
+    /// of a raw handle. No-op in release builds, where `DebugData` is never constructed anyway,
+    /// and under the `VK_EXT_debug_report` fallback, which has no object-naming equivalent.
+    pub fn set_name<H: vk::Handle>(&self, device: &ash::Device, handle: H, name: &str) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        let DebugData::Utils { debug_utils_loader, .. } = self else {
+            return;
+        };
+
+        // `CString::new` rejects interior nulls rather than truncating, and very long names are
+        // rarely useful in a validation message anyway; sanitize instead of erroring so a naming
+        // call never takes down an otherwise-fine debug build.
+        let name: String = name.chars().take_while(|&c| c != '\0').take(64).collect();
+        let name = CString::new(name).check_err("build debug object name");
+
+        let name_info = vk::DebugUtilsObjectNameInfoEXT {
+            object_type: H::TYPE,
+            object_handle: handle.as_raw(),
+            p_object_name: name.as_ptr(),
+            ..Default::default()
+        };
+
+        unsafe {
+            debug_utils_loader
+                .set_debug_utils_object_name(device.handle(), &name_info)
+                .check_err("set debug object name");
+        }
+    }
+}
+
+/// Tags `handle` with `name` if `debug_data` is present (i.e. in debug builds).
+pub fn set_name_opt<H: vk::Handle>(
+    debug_data: Option<&DebugData>,
+    device: &ash::Device,
+    handle: H,
+    name: &str,
+) {
+    if let Some(debug_data) = debug_data {
+        debug_data.set_name(device, handle, name);
+    }
 }
 
 unsafe extern "system" fn debug_callback(
@@ -548,6 +983,36 @@ unsafe extern "system" fn debug_callback(
     vk::FALSE
 }
 
+unsafe extern "system" fn debug_report_callback(
+    flags: vk::DebugReportFlagsEXT,
+    _object_type: vk::DebugReportObjectTypeEXT,
+    _object: u64,
+    _location: usize,
+    _message_code: i32,
+    p_layer_prefix: *const c_char,
+    p_message: *const c_char,
+    _user_data: *mut c_void,
+) -> vk::Bool32 {
+    let layer_prefix = cstr_to_cow(p_layer_prefix);
+    let msg = cstr_to_cow(p_message);
+
+    let text = format!("VK [{layer_prefix}] {msg}");
+
+    if flags.contains(vk::DebugReportFlagsEXT::ERROR) {
+        error!("{}", text);
+    } else if flags.contains(vk::DebugReportFlagsEXT::WARNING)
+        || flags.contains(vk::DebugReportFlagsEXT::PERFORMANCE_WARNING)
+    {
+        warn!("{}", text);
+    } else if flags.contains(vk::DebugReportFlagsEXT::INFORMATION) {
+        info!("{}", text);
+    } else {
+        debug!("{}", text);
+    }
+
+    vk::FALSE
+}
+
 fn label_fmt(label: &vk::DebugUtilsLabelEXT) -> String {
     let cstr = unsafe { CStr::from_ptr(label.p_label_name) };
     format!("{:?}", cstr.to_string_lossy())
@@ -574,16 +1039,27 @@ fn format_debug_items<T>(
     format!(" [{}: {}]", id, items)
 }
 
-pub fn pick_phys_device(instance: &ash::Instance, surface: &Surface) -> PhysDeviceInfo {
+pub fn pick_phys_device(
+    instance: &ash::Instance,
+    surface: &Surface,
+    requirements: &DeviceRequirements,
+) -> PhysDeviceInfo {
     let phys_devices =
         unsafe { instance.enumerate_physical_devices() }.check_err("get physical devices");
-    let mut phys_device_infos = gather_phys_device_infos(instance, surface, &phys_devices);
+    let mut phys_device_infos =
+        gather_phys_device_infos(instance, surface, &phys_devices, requirements);
 
     assert!(!phys_device_infos.is_empty(), "no suitable devices found");
 
-    phys_device_infos.sort_by_key(|d| device_type_to_priority(d.properties.device_type));
+    phys_device_infos.sort_by_key(|d| {
+        (
+            device_type_to_priority(d.properties.device_type),
+            std::cmp::Reverse(d.available_optional_extensions.len()),
+            std::cmp::Reverse(d.device_local_vram),
+        )
+    });
 
-    let phys_device_info = phys_device_infos[0];
+    let phys_device_info = phys_device_infos.remove(0);
 
     print_phys_device_info(instance, surface, &phys_device_info);
 
@@ -612,6 +1088,16 @@ fn print_phys_device_info(instance: &ash::Instance, surface: &Surface, info: &Ph
 
     if logger::verbose() {
         print_queue_family_infos(instance, phys_device, surface);
+
+        let gpu_info = &info.gpu_info;
+        debug!(
+            "Subgroup size: {}, max compute workgroup size: {:?}, \
+             max invocations: {}, max workgroup count: {:?}",
+            gpu_info.subgroup_size,
+            gpu_info.max_compute_work_group_size,
+            gpu_info.max_compute_work_group_invocations,
+            gpu_info.max_compute_work_group_count,
+        );
     }
 
     debug!(
@@ -660,6 +1146,7 @@ fn gather_phys_device_infos(
     instance: &ash::Instance,
     surface: &Surface,
     phys_devices: &[vk::PhysicalDevice],
+    requirements: &DeviceRequirements,
 ) -> Vec<PhysDeviceInfo> {
     let mut phys_device_infos = Vec::with_capacity(phys_devices.len());
 
@@ -669,14 +1156,28 @@ fn gather_phys_device_infos(
         let data = get_queue_family_data(instance, phys_device, surface);
         let extensions = unsafe { instance.enumerate_device_extension_properties(phys_device) }
             .check_err("enumerate device extensions");
+        let features = unsafe { instance.get_physical_device_features(phys_device) };
+
+        let meets_requirements = supports_required_queues(&data)
+            && supports_extensions(&extensions, requirements.required_extensions)
+            && features_satisfy(&requirements.required_features, &features);
 
-        if supports_required_queues(&data) && supports_required_extensions(&extensions) {
+        if meets_requirements {
             let queue_family_indices = get_queue_family_indices(&data);
+            let gpu_info = query_gpu_info(instance, phys_device, &properties);
+            let available_optional_extensions =
+                find_available_extensions(&extensions, requirements.optional_extensions);
+            let device_local_vram = query_device_local_vram(instance, phys_device);
 
             let info = PhysDeviceInfo {
                 phys_device,
                 properties,
                 queue_family_indices,
+                timestamp_period: properties.limits.timestamp_period,
+                gpu_info,
+                satisfied_features: requirements.required_features,
+                available_optional_extensions,
+                device_local_vram,
             };
 
             phys_device_infos.push(info);
@@ -686,14 +1187,99 @@ fn gather_phys_device_infos(
     phys_device_infos
 }
 
+/// Compares `vk::PhysicalDeviceFeatures` field-by-field via its `Bool32` layout: every field
+/// `required` sets to `TRUE` must also be `TRUE` in `available`.
+fn features_satisfy(
+    required: &vk::PhysicalDeviceFeatures,
+    available: &vk::PhysicalDeviceFeatures,
+) -> bool {
+    let field_count = size_of::<vk::PhysicalDeviceFeatures>() / size_of::<vk::Bool32>();
+
+    let required = unsafe {
+        std::slice::from_raw_parts((required as *const vk::PhysicalDeviceFeatures).cast::<vk::Bool32>(), field_count)
+    };
+    let available = unsafe {
+        std::slice::from_raw_parts((available as *const vk::PhysicalDeviceFeatures).cast::<vk::Bool32>(), field_count)
+    };
+
+    required.iter().zip(available).all(|(&req, &avail)| req == vk::FALSE || avail == vk::TRUE)
+}
+
+fn query_device_local_vram(instance: &ash::Instance, phys_device: vk::PhysicalDevice) -> u64 {
+    let mem_properties = unsafe { instance.get_physical_device_memory_properties(phys_device) };
+
+    mem_properties.memory_heaps[..mem_properties.memory_heap_count as usize]
+        .iter()
+        .filter(|heap| heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL))
+        .map(|heap| heap.size)
+        .sum()
+}
+
+fn find_available_extensions(
+    exts: &[vk::ExtensionProperties],
+    candidates: &'static [&'static str],
+) -> Vec<&'static str> {
+    candidates
+        .iter()
+        .copied()
+        .filter(|candidate| {
+            exts.iter().any(|ext| {
+                let name = unsafe { CStr::from_ptr(ext.extension_name.as_ptr()) };
+                name.to_str() == Ok(*candidate)
+            })
+        })
+        .collect()
+}
+
+fn query_gpu_info(
+    instance: &ash::Instance,
+    phys_device: vk::PhysicalDevice,
+    properties: &vk::PhysicalDeviceProperties,
+) -> GpuInfo {
+    let mut subgroup_properties = vk::PhysicalDeviceSubgroupProperties::default();
+    let mut properties2 =
+        vk::PhysicalDeviceProperties2::builder().push_next(&mut subgroup_properties).build();
+
+    unsafe { instance.get_physical_device_properties2(phys_device, &mut properties2) };
+
+    if !subgroup_properties.supported_stages.contains(vk::ShaderStageFlags::COMPUTE)
+        || !subgroup_properties
+            .supported_operations
+            .contains(vk::SubgroupFeatureFlags::BASIC)
+    {
+        warn!("Physical device does not advertise compute subgroup support");
+    }
+
+    let limits = &properties.limits;
+
+    GpuInfo {
+        subgroup_size: subgroup_properties.subgroup_size,
+        max_compute_work_group_size: limits.max_compute_work_group_size,
+        max_compute_work_group_invocations: limits.max_compute_work_group_invocations,
+        max_compute_work_group_count: limits.max_compute_work_group_count,
+    }
+}
+
+/// `timestampValidBits` of a queue family, i.e. how many low bits of a `cmd_write_timestamp`
+/// result are meaningful; the rest must be masked off before interpreting the value.
+pub fn query_timestamp_valid_bits(
+    instance: &ash::Instance,
+    phys_device: vk::PhysicalDevice,
+    queue_family_index: u32,
+) -> u32 {
+    let families = unsafe { instance.get_physical_device_queue_family_properties(phys_device) };
+
+    families[queue_family_index as usize].timestamp_valid_bits
+}
+
 fn supports_required_queues(data: &[QueueFamilyData]) -> bool {
     data.iter().any(|d| d.graphics)
         && data.iter().any(|d| d.present)
         && data.iter().any(|d| d.compute)
 }
 
-fn supports_required_extensions(exts: &[vk::ExtensionProperties]) -> bool {
-    let req_dev_exts_owned = convert_to_strings(REQ_DEVICE_EXTENSIONS);
+fn supports_extensions(exts: &[vk::ExtensionProperties], required: &[&str]) -> bool {
+    let req_dev_exts_owned = convert_to_strings(required);
     let req_dev_exts_cstrs = convert_to_c_strs(&req_dev_exts_owned);
 
     let mut support_found = vec![false; req_dev_exts_cstrs.len()];
@@ -942,28 +1528,44 @@ pub fn create_logical_device(instance: &ash::Instance, info: &PhysDeviceInfo) ->
         queue_create_infos.push(queue_create_info);
     }
 
-    let features = vk::PhysicalDeviceFeatures {
-        // fill_mode_non_solid: 1,
-        shader_clip_distance: 1,
-        ..Default::default()
-    };
+    // Enable exactly what `pick_phys_device` confirmed was requested-and-available, plus
+    // `pipeline_statistics_query` wherever the device supports it, since `ComputeTarget` creates
+    // its invocation-count query pool on exactly that condition.
+    let mut features = info.satisfied_features;
+    features.pipeline_statistics_query =
+        unsafe { instance.get_physical_device_features(info.phys_device) }.pipeline_statistics_query;
 
-    let req_dev_exts_owned = convert_to_strings(REQ_DEVICE_EXTENSIONS);
-    let req_dev_exts_cstrs = convert_to_c_strs(&req_dev_exts_owned);
-    let req_dev_exts_cptrs = convert_to_c_ptrs(&req_dev_exts_cstrs);
-
-    print_device_extensions(instance, info, &req_dev_exts_cptrs);
+    let dev_exts: Vec<&str> = REQ_DEVICE_EXTENSIONS
+        .iter()
+        .copied()
+        .chain(info.available_optional_extensions.iter().copied())
+        .collect();
+    let dev_exts_owned = convert_to_strings(&dev_exts);
+    let dev_exts_cstrs = convert_to_c_strs(&dev_exts_owned);
+    let dev_exts_cptrs = convert_to_c_ptrs(&dev_exts_cstrs);
+
+    print_device_extensions(instance, info, &dev_exts_cptrs);
+
+    // `VK_KHR_multiview` is a Vulkan 1.1+ feature struct, not one of the classic
+    // `vk::PhysicalDeviceFeatures` bits above, so it needs its own `p_next` chain entry before
+    // `SubpassBuilder::with_view_mask` render passes can actually be used.
+    let mut multiview_features =
+        info.available_optional_extensions.contains(&"VK_KHR_multiview").then(|| {
+            vk::PhysicalDeviceMultiviewFeatures { multiview: vk::TRUE, ..Default::default() }
+        });
 
-    let create_info = vk::DeviceCreateInfo {
+    let mut create_info = vk::DeviceCreateInfo {
         queue_create_info_count: to_u32(queue_create_infos.len()),
         p_queue_create_infos: queue_create_infos.as_ptr(),
-        enabled_extension_count: to_u32(req_dev_exts_cptrs.len()),
-        pp_enabled_extension_names: req_dev_exts_cptrs.as_ptr(),
+        enabled_extension_count: to_u32(dev_exts_cptrs.len()),
+        pp_enabled_extension_names: dev_exts_cptrs.as_ptr(),
         p_enabled_features: &features,
         ..Default::default()
     };
 
-    let _features = unsafe { instance.get_physical_device_features(info.phys_device) };
+    if let Some(multiview_features) = &mut multiview_features {
+        create_info.p_next = std::ptr::from_mut(multiview_features).cast();
+    }
 
     unsafe { instance.create_device(info.phys_device, &create_info, None) }
         .check_err("create device")
@@ -1080,13 +1682,39 @@ fn present_mode_to_priority(mode: vk::PresentModeKHR) -> u32 {
         vk::PresentModeKHR::FIFO_RELAXED => 2,
         vk::PresentModeKHR::MAILBOX => 3,
         vk::PresentModeKHR::FIFO => 4,
+        // Deliberately ranked last: shared-presentable-image modes need the caller to opt in via
+        // `find_shared_present_mode` and drive the single-image acquire/present loop themselves,
+        // so the default picker should never select them on its own.
+        vk::PresentModeKHR::SHARED_DEMAND_REFRESH
+        | vk::PresentModeKHR::SHARED_CONTINUOUS_REFRESH => 5,
         x => {
             warn!("Unexpected present mode: {}", x.as_raw());
-            5
+            6
         }
     }
 }
 
+/// Probes for the opt-in `VK_KHR_shared_presentable_image` low-latency path, preferring
+/// continuous refresh (the driver re-reads the image on its own schedule) over demand refresh
+/// (the caller must call `Swapchain::signal_present` after writing to the image).
+pub fn find_shared_present_mode(
+    phys_device: vk::PhysicalDevice,
+    surface: &Surface,
+) -> Option<vk::PresentModeKHR> {
+    let modes = unsafe {
+        surface.loader.get_physical_device_surface_present_modes(phys_device, surface.handle)
+    }
+    .check_err("get present modes");
+
+    if modes.contains(&vk::PresentModeKHR::SHARED_CONTINUOUS_REFRESH) {
+        Some(vk::PresentModeKHR::SHARED_CONTINUOUS_REFRESH)
+    } else if modes.contains(&vk::PresentModeKHR::SHARED_DEMAND_REFRESH) {
+        Some(vk::PresentModeKHR::SHARED_DEMAND_REFRESH)
+    } else {
+        None
+    }
+}
+
 pub fn create_command_pool(
     device: &ash::Device,
     queue_family_index: u32,
@@ -1153,6 +1781,21 @@ fn create_image_view(
     format: vk::Format,
     aspect_mask: vk::ImageAspectFlags,
     mip_levels: u32,
+) -> vk::ImageView {
+    create_image_view_layers(device, image, format, aspect_mask, mip_levels, 1)
+}
+
+/// `create_image_view`'s array-aware counterpart, for a multiview/stereo render target: a
+/// `layer_count` above 1 views the whole layer range as one `TYPE_2D_ARRAY` image view (e.g. a
+/// 2-layer color attachment with a `viewMask` of `0b11`), matching what `gl_ViewIndex` indexes
+/// into in the shader.
+fn create_image_view_layers(
+    device: &ash::Device,
+    image: vk::Image,
+    format: vk::Format,
+    aspect_mask: vk::ImageAspectFlags,
+    mip_levels: u32,
+    layer_count: u32,
 ) -> vk::ImageView {
     let components = vk::ComponentMapping::default();
 
@@ -1161,12 +1804,15 @@ fn create_image_view(
         base_mip_level: 0,
         level_count: mip_levels,
         base_array_layer: 0,
-        layer_count: 1,
+        layer_count,
     };
 
+    let view_type =
+        if layer_count > 1 { vk::ImageViewType::TYPE_2D_ARRAY } else { vk::ImageViewType::TYPE_2D };
+
     let create_info = vk::ImageViewCreateInfo {
         image,
-        view_type: vk::ImageViewType::TYPE_2D,
+        view_type,
         format,
         components,
         subresource_range,
@@ -1176,14 +1822,18 @@ fn create_image_view(
     unsafe { device.create_image_view(&create_info, None) }.check_err("create image view")
 }
 
-fn create_texture_sampler(device: &ash::Device) -> vk::Sampler {
+fn create_texture_sampler(device: &ash::Device, mip_levels: u32) -> vk::Sampler {
+    create_sampler(device, vk::Filter::NEAREST, mip_levels)
+}
+
+fn create_sampler(device: &ash::Device, filter: vk::Filter, mip_levels: u32) -> vk::Sampler {
     let create_info = vk::SamplerCreateInfo {
-        mag_filter: vk::Filter::NEAREST,
-        min_filter: vk::Filter::NEAREST,
+        mag_filter: filter,
+        min_filter: filter,
         mipmap_mode: vk::SamplerMipmapMode::LINEAR,
         mip_lod_bias: 0.0,
         min_lod: 0.0,
-        max_lod: 0.0,
+        max_lod: to_f32(mip_levels),
         address_mode_u: vk::SamplerAddressMode::CLAMP_TO_BORDER,
         address_mode_v: vk::SamplerAddressMode::CLAMP_TO_BORDER,
         address_mode_w: vk::SamplerAddressMode::CLAMP_TO_BORDER,
@@ -1199,6 +1849,29 @@ fn create_texture_sampler(device: &ash::Device) -> vk::Sampler {
     unsafe { device.create_sampler(&create_info, None).check_err("create sampler") }
 }
 
+/// Framebuffer over a single color attachment, for render passes that don't share the
+/// swapchain's depth/color setup (e.g. an offscreen post-processing pass).
+pub fn create_single_attachment_framebuffer(
+    device: &ash::Device,
+    image_view: vk::ImageView,
+    extent: vk::Extent2D,
+    render_pass: vk::RenderPass,
+) -> vk::Framebuffer {
+    let attachments = [image_view];
+
+    let create_info = vk::FramebufferCreateInfo {
+        render_pass,
+        attachment_count: to_u32(attachments.len()),
+        p_attachments: attachments.as_ptr(),
+        width: extent.width,
+        height: extent.height,
+        layers: 1,
+        ..Default::default()
+    };
+
+    unsafe { device.create_framebuffer(&create_info, None) }.check_err("create framebuffer")
+}
+
 pub fn create_framebuffers(
     device: &ash::Device,
     image_views: &[vk::ImageView],
@@ -1324,7 +1997,7 @@ pub unsafe fn create_buffer(
     (buffer, memory)
 }
 
-fn find_memory_type(
+pub(super) fn find_memory_type(
     req_type: u32,
     req_properties: vk::MemoryPropertyFlags,
     mem_properties: &vk::PhysicalDeviceMemoryProperties,
@@ -1344,7 +2017,11 @@ fn find_memory_type(
     None
 }
 
-fn upload_to_buffer_memory<T: Copy>(device: &ash::Device, memory: vk::DeviceMemory, data: &[T]) {
+pub(super) fn upload_to_buffer_memory<T: Copy>(
+    device: &ash::Device,
+    memory: vk::DeviceMemory,
+    data: &[T],
+) {
     let size = size_of_val(data) as u64;
 
     let memory_range = vk::MappedMemoryRange {
@@ -1368,7 +2045,7 @@ fn upload_to_buffer_memory<T: Copy>(device: &ash::Device, memory: vk::DeviceMemo
     }
 }
 
-fn copy_buffers(
+pub(super) fn copy_buffers(
     device: &ash::Device,
     command_pool: vk::CommandPool,
     queue: vk::Queue,
@@ -1390,7 +2067,7 @@ fn copy_buffers(
     }
 }
 
-unsafe fn start_single_command(
+pub(super) unsafe fn start_single_command(
     device: &ash::Device,
     command_pool: vk::CommandPool,
 ) -> vk::CommandBuffer {
@@ -1404,7 +2081,7 @@ unsafe fn start_single_command(
     cmd_buffer
 }
 
-unsafe fn end_single_command(
+pub(super) unsafe fn end_single_command(
     device: &ash::Device,
     command_pool: vk::CommandPool,
     cmd_buffer: vk::CommandBuffer,
@@ -1422,18 +2099,39 @@ unsafe fn end_single_command(
     device.free_command_buffers(command_pool, &[cmd_buffer]);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_texture_image(
+    instance: &ash::Instance,
+    phys_device: vk::PhysicalDevice,
     device: &ash::Device,
     device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
     command_pool: vk::CommandPool,
     graphics_queue: vk::Queue,
     path: &'static str,
-) -> (vk::Image, vk::DeviceMemory, vk::ImageLayout) {
+) -> (vk::Image, vk::DeviceMemory, vk::ImageLayout, u32) {
     let texture = Image::from_file(path).check_err("decode image");
     let format = vk::Format::R8G8B8A8_SRGB;
     let texture_size = texture.size_x * texture.size_y * 4;
     let final_layout = vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL;
 
+    let supports_linear_blit = find_supported_format(
+        instance,
+        phys_device,
+        &[format],
+        true,
+        vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR,
+    )
+    .is_some();
+
+    let mip_levels =
+        if supports_linear_blit { calc_mip_levels(texture.size_x, texture.size_y) } else { 1 };
+
+    let mut usage = vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED;
+
+    if mip_levels > 1 {
+        usage |= vk::ImageUsageFlags::TRANSFER_SRC;
+    }
+
     let (staging_buffer, staging_memory) = unsafe {
         create_buffer(
             device,
@@ -1452,14 +2150,21 @@ fn create_texture_image(
         format,
         texture.size_x,
         texture.size_y,
-        vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED,
+        mip_levels,
+        1,
+        vk::ImageCreateFlags::empty(),
+        usage,
     );
 
+    let full_mip_range =
+        vk::ImageSubresourceRange { level_count: mip_levels, ..BASE_SUBRESOURCE_RANGE };
+
     transition_image_layout(
         device,
         command_pool,
         graphics_queue,
         image,
+        full_mip_range,
         vk::ImageLayout::UNDEFINED,
         vk::ImageLayout::TRANSFER_DST_OPTIMAL,
     );
@@ -1472,34 +2177,171 @@ fn create_texture_image(
         image,
         texture.size_x,
         texture.size_y,
+        1,
+        texture_size.into(),
     );
 
-    transition_image_layout(
-        device,
-        command_pool,
-        graphics_queue,
-        image,
-        vk::ImageLayout::TRANSFER_DST_OPTIMAL,
-        final_layout,
-    );
+    if mip_levels > 1 {
+        generate_mipmaps(
+            instance,
+            phys_device,
+            device,
+            command_pool,
+            graphics_queue,
+            image,
+            format,
+            texture.size_x,
+            texture.size_y,
+            mip_levels,
+        );
+    } else {
+        transition_image_layout(
+            device,
+            command_pool,
+            graphics_queue,
+            image,
+            BASE_SUBRESOURCE_RANGE,
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            final_layout,
+        );
+    }
 
     unsafe {
         device.destroy_buffer(staging_buffer, None);
         device.free_memory(staging_memory, None);
     }
 
-    (image, image_memory, final_layout)
+    (image, image_memory, final_layout, mip_levels)
+}
+
+/// `floor(log2(max(width, height))) + 1`, i.e. the number of mip levels down to a 1x1 image.
+fn calc_mip_levels(width: u32, height: u32) -> u32 {
+    width.max(height).ilog2() + 1
+}
+
+/// Blits each mip level from the one below it, leaving every level in `SHADER_READ_ONLY_OPTIMAL`.
+/// Level 0 must already hold image data and be in `TRANSFER_DST_OPTIMAL`.
+#[allow(clippy::too_many_arguments)]
+fn generate_mipmaps(
+    instance: &ash::Instance,
+    phys_device: vk::PhysicalDevice,
+    device: &ash::Device,
+    command_pool: vk::CommandPool,
+    queue: vk::Queue,
+    image: vk::Image,
+    format: vk::Format,
+    width: u32,
+    height: u32,
+    mip_levels: u32,
+) {
+    find_supported_format(
+        instance,
+        phys_device,
+        &[format],
+        true,
+        vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR,
+    )
+    .check_err("blit a mip chain: format doesn't support linear filtering");
+
+    let mut mip_width = width;
+    let mut mip_height = height;
+
+    unsafe {
+        let cmd_buffer = start_single_command(device, command_pool);
+
+        for level in 1..mip_levels {
+            let src_level = level - 1;
+            let next_width = (mip_width / 2).max(1);
+            let next_height = (mip_height / 2).max(1);
+
+            record_image_layout_transition(
+                device,
+                cmd_buffer,
+                vk::QUEUE_FAMILY_IGNORED,
+                vk::QUEUE_FAMILY_IGNORED,
+                image,
+                vk::ImageSubresourceRange { base_mip_level: src_level, ..BASE_SUBRESOURCE_RANGE },
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            );
+
+            let blit = vk::ImageBlit {
+                src_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    mip_level: src_level,
+                    base_array_layer: 0,
+                    layer_count: 1,
+                },
+                src_offsets: [
+                    vk::Offset3D::default(),
+                    vk::Offset3D { x: to_i32(mip_width), y: to_i32(mip_height), z: 1 },
+                ],
+                dst_subresource: vk::ImageSubresourceLayers {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    mip_level: level,
+                    base_array_layer: 0,
+                    layer_count: 1,
+                },
+                dst_offsets: [
+                    vk::Offset3D::default(),
+                    vk::Offset3D { x: to_i32(next_width), y: to_i32(next_height), z: 1 },
+                ],
+            };
+
+            device.cmd_blit_image(
+                cmd_buffer,
+                image,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                &[blit],
+                vk::Filter::LINEAR,
+            );
+
+            record_image_layout_transition(
+                device,
+                cmd_buffer,
+                vk::QUEUE_FAMILY_IGNORED,
+                vk::QUEUE_FAMILY_IGNORED,
+                image,
+                vk::ImageSubresourceRange { base_mip_level: src_level, ..BASE_SUBRESOURCE_RANGE },
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            );
+
+            mip_width = next_width;
+            mip_height = next_height;
+        }
+
+        record_image_layout_transition(
+            device,
+            cmd_buffer,
+            vk::QUEUE_FAMILY_IGNORED,
+            vk::QUEUE_FAMILY_IGNORED,
+            image,
+            vk::ImageSubresourceRange { base_mip_level: mip_levels - 1, ..BASE_SUBRESOURCE_RANGE },
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        );
+
+        end_single_command(device, command_pool, cmd_buffer, queue);
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_image(
     device: &ash::Device,
     device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
     format: vk::Format,
     width: u32,
     height: u32,
+    mip_levels: u32,
+    array_layers: u32,
+    flags: vk::ImageCreateFlags,
     usage: vk::ImageUsageFlags,
 ) -> (vk::Image, vk::DeviceMemory) {
     let create_info = vk::ImageCreateInfo {
+        flags,
         image_type: vk::ImageType::TYPE_2D,
         format,
         extent: vk::Extent3D {
@@ -1507,8 +2349,8 @@ fn create_image(
             height,
             depth: 1,
         },
-        mip_levels: 1,
-        array_layers: 1,
+        mip_levels,
+        array_layers,
         samples: vk::SampleCountFlags::TYPE_1,
         tiling: vk::ImageTiling::OPTIMAL,
         usage,
@@ -1543,11 +2385,13 @@ fn create_image(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn transition_image_layout(
     device: &ash::Device,
     command_pool: vk::CommandPool,
     queue: vk::Queue,
     image: vk::Image,
+    subresource_range: vk::ImageSubresourceRange,
     old_layout: vk::ImageLayout,
     new_layout: vk::ImageLayout,
 ) {
@@ -1560,6 +2404,7 @@ fn transition_image_layout(
             vk::QUEUE_FAMILY_IGNORED,
             vk::QUEUE_FAMILY_IGNORED,
             image,
+            subresource_range,
             old_layout,
             new_layout,
         );
@@ -1568,17 +2413,17 @@ fn transition_image_layout(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn record_image_layout_transition(
     device: &ash::Device,
     cmd_buffer: vk::CommandBuffer,
     src_queue_family_index: u32,
     dst_queue_family_index: u32,
     image: vk::Image,
+    subresource_range: vk::ImageSubresourceRange,
     old_layout: vk::ImageLayout,
     new_layout: vk::ImageLayout,
 ) {
-    let subresource_range = BASE_SUBRESOURCE_RANGE;
-
     let (src_access_mask, dst_access_mask, src_stage, dst_stage) =
         image_layout_transition_flags(old_layout, new_layout);
 
@@ -1607,33 +2452,58 @@ pub fn record_image_layout_transition(
     }
 }
 
+/// Derives the barrier's access masks and stages from the layouts alone rather than enumerating
+/// every `(old, new)` pair by hand, so depth attachments, compute images and any new transition
+/// that reads/writes one of the layouts below "just works" without growing a combinatorial match.
 fn image_layout_transition_flags(
     old_layout: vk::ImageLayout,
     new_layout: vk::ImageLayout,
 ) -> (vk::AccessFlags, vk::AccessFlags, vk::PipelineStageFlags, vk::PipelineStageFlags) {
-    match (old_layout, new_layout) {
-        (vk::ImageLayout::UNDEFINED, vk::ImageLayout::TRANSFER_DST_OPTIMAL) => (
-            vk::AccessFlags::empty(),
-            vk::AccessFlags::TRANSFER_WRITE,
-            vk::PipelineStageFlags::TOP_OF_PIPE,
-            vk::PipelineStageFlags::TRANSFER,
+    let (src_access_mask, src_stage) = layout_access_and_stage(old_layout);
+    let (dst_access_mask, dst_stage) = layout_access_and_stage(new_layout);
+
+    (src_access_mask, dst_access_mask, src_stage, dst_stage)
+}
+
+/// Access mask and pipeline stage at which a given layout is read or written.
+fn layout_access_and_stage(layout: vk::ImageLayout) -> (vk::AccessFlags, vk::PipelineStageFlags) {
+    match layout {
+        vk::ImageLayout::UNDEFINED => {
+            (vk::AccessFlags::empty(), vk::PipelineStageFlags::TOP_OF_PIPE)
+        }
+        vk::ImageLayout::TRANSFER_DST_OPTIMAL => {
+            (vk::AccessFlags::TRANSFER_WRITE, vk::PipelineStageFlags::TRANSFER)
+        }
+        vk::ImageLayout::TRANSFER_SRC_OPTIMAL => {
+            (vk::AccessFlags::TRANSFER_READ, vk::PipelineStageFlags::TRANSFER)
+        }
+        vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL => {
+            (vk::AccessFlags::SHADER_READ, vk::PipelineStageFlags::FRAGMENT_SHADER)
+        }
+        vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL => (
+            vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ
+                | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS,
         ),
-        (vk::ImageLayout::TRANSFER_DST_OPTIMAL, vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL) => (
-            vk::AccessFlags::TRANSFER_WRITE,
-            vk::AccessFlags::SHADER_READ,
-            vk::PipelineStageFlags::TRANSFER,
-            vk::PipelineStageFlags::FRAGMENT_SHADER,
+        vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL => (
+            vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+            vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
         ),
-        (vk::ImageLayout::UNDEFINED, vk::ImageLayout::GENERAL) => (
-            vk::AccessFlags::empty(),
-            vk::AccessFlags::SHADER_READ,
-            vk::PipelineStageFlags::TOP_OF_PIPE,
+        vk::ImageLayout::GENERAL => (
+            vk::AccessFlags::SHADER_READ | vk::AccessFlags::SHADER_WRITE,
             vk::PipelineStageFlags::COMPUTE_SHADER,
         ),
-        _ => panic!("unexpected layout transition: {:?} -> {:?}", old_layout, new_layout),
+        vk::ImageLayout::PRESENT_SRC_KHR => {
+            (vk::AccessFlags::empty(), vk::PipelineStageFlags::BOTTOM_OF_PIPE)
+        }
+        _ => panic!("unhandled image layout in barrier derivation: {layout:?}"),
     }
 }
 
+/// Copies a packed staging buffer into `image`, one region per array layer (cubemap face or
+/// texture-array slice). `layer_size` is the byte size of a single layer within `buffer`; layer
+/// `i`'s data is expected at `buffer_offset = i * layer_size`.
+#[allow(clippy::too_many_arguments)]
 fn copy_buffer_to_image(
     device: &ash::Device,
     command_pool: vk::CommandPool,
@@ -1642,14 +2512,9 @@ fn copy_buffer_to_image(
     image: vk::Image,
     width: u32,
     height: u32,
+    layer_count: u32,
+    layer_size: u64,
 ) {
-    let image_subresource = vk::ImageSubresourceLayers {
-        aspect_mask: vk::ImageAspectFlags::COLOR,
-        mip_level: 0,
-        base_array_layer: 0,
-        layer_count: 1,
-    };
-
     let image_offset = vk::Offset3D::default();
 
     let image_extent = vk::Extent3D {
@@ -1658,14 +2523,21 @@ fn copy_buffer_to_image(
         depth: 1,
     };
 
-    let region = vk::BufferImageCopy {
-        buffer_offset: 0,
-        buffer_row_length: 0,
-        buffer_image_height: 0,
-        image_subresource,
-        image_offset,
-        image_extent,
-    };
+    let regions: Vec<vk::BufferImageCopy> = (0..layer_count)
+        .map(|layer| vk::BufferImageCopy {
+            buffer_offset: u64::from(layer) * layer_size,
+            buffer_row_length: 0,
+            buffer_image_height: 0,
+            image_subresource: vk::ImageSubresourceLayers {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                mip_level: 0,
+                base_array_layer: layer,
+                layer_count: 1,
+            },
+            image_offset,
+            image_extent,
+        })
+        .collect();
 
     unsafe {
         let cmd_buffer = start_single_command(device, command_pool);
@@ -1675,25 +2547,103 @@ fn copy_buffer_to_image(
             buffer,
             image,
             vk::ImageLayout::TRANSFER_DST_OPTIMAL,
-            &[region],
+            &regions,
         );
 
         end_single_command(device, command_pool, cmd_buffer, queue);
     }
 }
 
+/// Records a copy of `image` (which must already be in `TRANSFER_SRC_OPTIMAL`) into `buffer`,
+/// tightly packed (`buffer_row_length`/`buffer_image_height` left at `0`, meaning "same as the
+/// image"), for `Renderer::read_back_frame`'s host-visible frame capture.
+pub fn record_copy_image_to_buffer(
+    device: &ash::Device,
+    cmd_buffer: vk::CommandBuffer,
+    image: vk::Image,
+    buffer: vk::Buffer,
+    extent: vk::Extent2D,
+) {
+    let region = vk::BufferImageCopy {
+        buffer_offset: 0,
+        buffer_row_length: 0,
+        buffer_image_height: 0,
+        image_subresource: vk::ImageSubresourceLayers {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            mip_level: 0,
+            base_array_layer: 0,
+            layer_count: 1,
+        },
+        image_offset: vk::Offset3D::default(),
+        image_extent: vk::Extent3D { width: extent.width, height: extent.height, depth: 1 },
+    };
+
+    unsafe {
+        device.cmd_copy_image_to_buffer(
+            cmd_buffer,
+            image,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            buffer,
+            &[region],
+        );
+    }
+}
+
+/// Records an unscaled, same-`extent` copy of `src` (already `TRANSFER_SRC_OPTIMAL`) into `dst`
+/// (already `TRANSFER_DST_OPTIMAL`), for `Renderer`'s per-frame scene capture, which copies rather
+/// than blits since the capture texture is always sized to match the swapchain exactly.
+pub fn record_copy_image_to_image(
+    device: &ash::Device,
+    cmd_buffer: vk::CommandBuffer,
+    src: vk::Image,
+    dst: vk::Image,
+    extent: vk::Extent2D,
+) {
+    let copy = vk::ImageCopy {
+        src_subresource: vk::ImageSubresourceLayers {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            mip_level: 0,
+            base_array_layer: 0,
+            layer_count: 1,
+        },
+        src_offset: vk::Offset3D::default(),
+        dst_subresource: vk::ImageSubresourceLayers {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            mip_level: 0,
+            base_array_layer: 0,
+            layer_count: 1,
+        },
+        dst_offset: vk::Offset3D::default(),
+        extent: vk::Extent3D { width: extent.width, height: extent.height, depth: 1 },
+    };
+
+    unsafe {
+        device.cmd_copy_image(
+            cmd_buffer,
+            src,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            dst,
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            &[copy],
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn create_host_visible_shader_buffers<T>(
     device: &ash::Device,
     device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
     usage: vk::BufferUsageFlags,
     size: u64,
     copies: usize,
+    debug_data: Option<&DebugData>,
+    name: &str,
 ) -> (Vec<vk::Buffer>, Vec<vk::DeviceMemory>, Vec<*mut T>) {
     let mut buffers = Vec::with_capacity(copies);
     let mut memories = Vec::with_capacity(copies);
     let mut mappings = Vec::with_capacity(copies);
 
-    for _ in 0..copies {
+    for i in 0..copies {
         unsafe {
             let (buffer, memory) = create_buffer(
                 device,
@@ -1707,6 +2657,8 @@ pub fn create_host_visible_shader_buffers<T>(
                 .check_err("map memory")
                 .cast::<T>();
 
+            set_name_opt(debug_data, device, buffer, &format!("{name}[{i}]"));
+
             buffers.push(buffer);
             memories.push(memory);
             mappings.push(mapping);
@@ -1720,11 +2672,77 @@ pub fn create_uniform_buffers<T>(
     device: &ash::Device,
     device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
     copies: usize,
+    debug_data: Option<&DebugData>,
+    name: &str,
 ) -> (Vec<vk::Buffer>, Vec<vk::DeviceMemory>, Vec<*mut T>) {
     let usage = vk::BufferUsageFlags::UNIFORM_BUFFER;
     let size = size_of::<T>() as u64;
 
-    create_host_visible_shader_buffers(device, device_mem_properties, usage, size, copies)
+    create_host_visible_shader_buffers(
+        device,
+        device_mem_properties,
+        usage,
+        size,
+        copies,
+        debug_data,
+        name,
+    )
+}
+
+/// Storage-buffer counterpart to `create_uniform_buffers`, for a GPU-written buffer (e.g. particle
+/// positions advanced by a compute shader each frame) that's host-visible so the CPU can seed or
+/// read back its contents.
+pub fn create_storage_buffers<T>(
+    device: &ash::Device,
+    device_mem_properties: &vk::PhysicalDeviceMemoryProperties,
+    copies: usize,
+    debug_data: Option<&DebugData>,
+    name: &str,
+) -> (Vec<vk::Buffer>, Vec<vk::DeviceMemory>, Vec<*mut T>) {
+    let usage = vk::BufferUsageFlags::STORAGE_BUFFER;
+    let size = size_of::<T>() as u64;
+
+    create_host_visible_shader_buffers(
+        device,
+        device_mem_properties,
+        usage,
+        size,
+        copies,
+        debug_data,
+        name,
+    )
+}
+
+/// Barrier handing a storage buffer written by a compute shader over to the vertex stage, e.g.
+/// after a compute pass advances particle positions that the next draw call reads as vertex input.
+pub fn record_compute_to_vertex_input_barrier(
+    device: &ash::Device,
+    cmd_buffer: vk::CommandBuffer,
+    buffer: vk::Buffer,
+    size: u64,
+) {
+    let barrier = vk::BufferMemoryBarrier {
+        src_access_mask: vk::AccessFlags::SHADER_WRITE,
+        dst_access_mask: vk::AccessFlags::VERTEX_ATTRIBUTE_READ,
+        src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+        dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+        buffer,
+        offset: 0,
+        size,
+        ..Default::default()
+    };
+
+    unsafe {
+        device.cmd_pipeline_barrier(
+            cmd_buffer,
+            vk::PipelineStageFlags::COMPUTE_SHADER,
+            vk::PipelineStageFlags::VERTEX_INPUT,
+            vk::DependencyFlags::empty(),
+            &[],
+            &[barrier],
+            &[],
+        );
+    }
 }
 
 pub fn find_depth_format(instance: &ash::Instance, phys_device: vk::PhysicalDevice) -> vk::Format {
@@ -1773,11 +2791,180 @@ fn find_supported_format(
     None
 }
 
-pub fn create_semaphores(device: &ash::Device, copies: usize) -> Vec<vk::Semaphore> {
+/// Whether `src_format` can be the source and `dst_format` the destination of `cmd_blit_image` on
+/// this physical device (`VK_FORMAT_FEATURE_BLIT_SRC_BIT`/`BLIT_DST_BIT`). A scaling presentation
+/// path (an offscreen fixed-resolution image blitted into a differently-sized swapchain image)
+/// needs both; `false` means falling back to `cmd_copy_image`, which has no scaling or filtering
+/// but works on any pair of matching-extent, matching-format images.
+pub fn formats_support_blit(
+    instance: &ash::Instance,
+    phys_device: vk::PhysicalDevice,
+    src_format: vk::Format,
+    dst_format: vk::Format,
+) -> bool {
+    let src_props =
+        unsafe { instance.get_physical_device_format_properties(phys_device, src_format) };
+    let dst_props =
+        unsafe { instance.get_physical_device_format_properties(phys_device, dst_format) };
+
+    src_props.optimal_tiling_features.contains(vk::FormatFeatureFlags::BLIT_SRC)
+        && dst_props.optimal_tiling_features.contains(vk::FormatFeatureFlags::BLIT_DST)
+}
+
+/// A centered, aspect-preserving `vk::ImageBlit` that stretches all of `src_extent` into the
+/// largest rectangle of `dst_extent` sharing `src_extent`'s aspect ratio, letterboxing whatever of
+/// `dst_extent` doesn't fit — for presenting a fixed-resolution offscreen render (retro/pixel-art
+/// styles, or a fixed render cost) at an arbitrary window size. `integer_scale` floors the scale
+/// factor to a whole number first (so pixel-art content stays crisp instead of shimmering under
+/// non-integer resampling), but only while scaling up — a window too small to fit a whole-number
+/// multiple still shrinks the image by a fractional amount rather than disappearing entirely.
+pub fn compute_letterboxed_blit(
+    src_extent: vk::Extent2D,
+    dst_extent: vk::Extent2D,
+    integer_scale: bool,
+) -> vk::ImageBlit {
+    let scale_x = to_f32(dst_extent.width) / to_f32(src_extent.width);
+    let scale_y = to_f32(dst_extent.height) / to_f32(src_extent.height);
+    let scale = scale_x.min(scale_y);
+    // Only round down to a whole number when scaling up; a fixed-resolution render that doesn't
+    // fit a smaller window at all still needs to shrink by a fractional amount.
+    let scale = if integer_scale && scale >= 1.0 { scale.floor() } else { scale };
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let dst_width = ((to_f32(src_extent.width) * scale) as u32).max(1);
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let dst_height = ((to_f32(src_extent.height) * scale) as u32).max(1);
+
+    let dst_x = dst_extent.width.saturating_sub(dst_width) / 2;
+    let dst_y = dst_extent.height.saturating_sub(dst_height) / 2;
+
+    vk::ImageBlit {
+        src_subresource: vk::ImageSubresourceLayers {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            mip_level: 0,
+            base_array_layer: 0,
+            layer_count: 1,
+        },
+        src_offsets: [
+            vk::Offset3D::default(),
+            vk::Offset3D { x: to_i32(src_extent.width), y: to_i32(src_extent.height), z: 1 },
+        ],
+        dst_subresource: vk::ImageSubresourceLayers {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            mip_level: 0,
+            base_array_layer: 0,
+            layer_count: 1,
+        },
+        dst_offsets: [
+            vk::Offset3D { x: to_i32(dst_x), y: to_i32(dst_y), z: 0 },
+            vk::Offset3D { x: to_i32(dst_x + dst_width), y: to_i32(dst_y + dst_height), z: 1 },
+        ],
+    }
+}
+
+/// Presents `src_image` (currently in `src_layout`, e.g. the `COLOR_ATTACHMENT_OPTIMAL` a fixed-
+/// resolution offscreen pass just rendered into) onto `dst_image`/`dst_extent` (a swapchain
+/// image), scaling via `cmd_blit_image` when `supports_blit` (see `formats_support_blit`) allows
+/// it, or falling back to an unscaled `cmd_copy_image` otherwise (which requires `src_extent` to
+/// equal `dst_extent`). `integer_scale` only affects the blit path: `NEAREST` filtering for a
+/// crisp whole-number scale instead of `LINEAR`. Leaves `src_image` in `TRANSFER_SRC_OPTIMAL` and
+/// `dst_image` in `TRANSFER_DST_OPTIMAL`; the caller transitions `dst_image` on to
+/// `PRESENT_SRC_KHR` before presenting it.
+#[allow(clippy::too_many_arguments)]
+pub fn record_present_blit(
+    device: &ash::Device,
+    cmd_buffer: vk::CommandBuffer,
+    src_image: vk::Image,
+    src_layout: vk::ImageLayout,
+    src_extent: vk::Extent2D,
+    dst_image: vk::Image,
+    dst_extent: vk::Extent2D,
+    supports_blit: bool,
+    integer_scale: bool,
+) {
+    record_image_layout_transition(
+        device,
+        cmd_buffer,
+        vk::QUEUE_FAMILY_IGNORED,
+        vk::QUEUE_FAMILY_IGNORED,
+        src_image,
+        BASE_SUBRESOURCE_RANGE,
+        src_layout,
+        vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+    );
+
+    record_image_layout_transition(
+        device,
+        cmd_buffer,
+        vk::QUEUE_FAMILY_IGNORED,
+        vk::QUEUE_FAMILY_IGNORED,
+        dst_image,
+        BASE_SUBRESOURCE_RANGE,
+        vk::ImageLayout::UNDEFINED,
+        vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+    );
+
+    if supports_blit {
+        let blit = compute_letterboxed_blit(src_extent, dst_extent, integer_scale);
+        let filter = if integer_scale { vk::Filter::NEAREST } else { vk::Filter::LINEAR };
+
+        unsafe {
+            device.cmd_blit_image(
+                cmd_buffer,
+                src_image,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                dst_image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                &[blit],
+                filter,
+            );
+        }
+    } else {
+        warn!("swapchain format doesn't support blit, falling back to an unscaled copy");
+
+        let copy = vk::ImageCopy {
+            src_subresource: vk::ImageSubresourceLayers {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: 1,
+            },
+            src_offset: vk::Offset3D::default(),
+            dst_subresource: vk::ImageSubresourceLayers {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: 1,
+            },
+            dst_offset: vk::Offset3D::default(),
+            extent: vk::Extent3D { width: src_extent.width, height: src_extent.height, depth: 1 },
+        };
+
+        unsafe {
+            device.cmd_copy_image(
+                cmd_buffer,
+                src_image,
+                vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                dst_image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                &[copy],
+            );
+        }
+    }
+}
+
+pub fn create_semaphores(
+    device: &ash::Device,
+    copies: usize,
+    debug_data: Option<&DebugData>,
+    name: &str,
+) -> Vec<vk::Semaphore> {
     let mut semaphores = Vec::with_capacity(copies);
 
-    for _ in 0..copies {
-        semaphores.push(create_semaphore(device));
+    for i in 0..copies {
+        let semaphore = create_semaphore(device);
+        set_name_opt(debug_data, device, semaphore, &format!("{name}[{i}]"));
+        semaphores.push(semaphore);
     }
 
     semaphores
@@ -1789,11 +2976,19 @@ fn create_semaphore(device: &ash::Device) -> vk::Semaphore {
     unsafe { device.create_semaphore(&create_info, None) }.check_err("create semaphore")
 }
 
-pub fn create_fences(device: &ash::Device, signaled: bool, copies: usize) -> Vec<vk::Fence> {
+pub fn create_fences(
+    device: &ash::Device,
+    signaled: bool,
+    copies: usize,
+    debug_data: Option<&DebugData>,
+    name: &str,
+) -> Vec<vk::Fence> {
     let mut fences = Vec::with_capacity(copies);
 
-    for _ in 0..copies {
-        fences.push(create_fence(device, signaled));
+    for i in 0..copies {
+        let fence = create_fence(device, signaled);
+        set_name_opt(debug_data, device, fence, &format!("{name}[{i}]"));
+        fences.push(fence);
     }
 
     fences
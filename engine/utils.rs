@@ -1,7 +1,9 @@
 use std::borrow::Cow;
 use std::ffi::{c_char, CStr, CString};
-use std::fmt::Display;
+use std::fmt::{self, Display};
+use std::num::NonZeroU16;
 
+use anyhow::{anyhow, Result};
 use glam::{Mat3, Mat4};
 use log::debug;
 
@@ -130,6 +132,158 @@ pub fn pack_to_u32s(bytes: &[u8]) -> Vec<u32> {
         .collect()
 }
 
+/// Bounds-checked binary accessors for parsing untrusted asset files (maps, images), unlike
+/// `pack_to_u32s`/`any_as_bytes` which panic or assume well-formed input. Every `c_*` accessor
+/// returns a descriptive error instead of panicking on truncated data; the `o_*` family is the
+/// same thing as an `Option`, for callers that just want to treat short reads as "absent".
+pub trait BinRead<'d> {
+    fn c_u16_le(self, i: usize) -> Result<u16>;
+    fn c_u16_be(self, i: usize) -> Result<u16>;
+    fn c_i16_le(self, i: usize) -> Result<i16>;
+    fn c_i16_be(self, i: usize) -> Result<i16>;
+    fn c_u32_le(self, i: usize) -> Result<u32>;
+    fn c_u32_be(self, i: usize) -> Result<u32>;
+    fn c_i32_le(self, i: usize) -> Result<i32>;
+    fn c_i32_be(self, i: usize) -> Result<i32>;
+
+    /// A `[range.start..range.end)` slice, e.g. for a length-prefixed blob embedded in the file.
+    fn c_data(self, range: std::ops::Range<usize>) -> Result<&'d [u8]>;
+
+    /// A four-byte "four-character-code" tag, e.g. a RIFF-style chunk id.
+    fn c_ident(self, i: usize) -> Result<[u8; 4]>;
+
+    fn o_u16_le(self, i: usize) -> Option<u16>;
+    fn o_u16_be(self, i: usize) -> Option<u16>;
+    fn o_i16_le(self, i: usize) -> Option<i16>;
+    fn o_i16_be(self, i: usize) -> Option<i16>;
+    fn o_u32_le(self, i: usize) -> Option<u32>;
+    fn o_u32_be(self, i: usize) -> Option<u32>;
+    fn o_i32_le(self, i: usize) -> Option<i32>;
+    fn o_i32_be(self, i: usize) -> Option<i32>;
+    fn o_data(self, range: std::ops::Range<usize>) -> Option<&'d [u8]>;
+    fn o_ident(self, i: usize) -> Option<[u8; 4]>;
+}
+
+/// Defines one `c_*`/`o_*` pair reading a `$width`-byte, `$ty`-typed integer at a given offset
+/// via `$from_bytes` (`from_le_bytes`/`from_be_bytes`).
+macro_rules! int_readers {
+    ($c_name:ident, $o_name:ident, $ty:ty, $width:expr, $from_bytes:ident) => {
+        fn $c_name(self, i: usize) -> Result<$ty> {
+            let bytes: [u8; $width] = self
+                .get(i..i + $width)
+                .ok_or_else(|| anyhow!("not enough data at offset {}", i))?
+                .try_into()
+                .unwrap_or_else(|_| unreachable!());
+
+            Ok(<$ty>::$from_bytes(bytes))
+        }
+
+        fn $o_name(self, i: usize) -> Option<$ty> {
+            self.$c_name(i).ok()
+        }
+    };
+}
+
+impl<'d> BinRead<'d> for &'d [u8] {
+    int_readers!(c_u16_le, o_u16_le, u16, 2, from_le_bytes);
+    int_readers!(c_u16_be, o_u16_be, u16, 2, from_be_bytes);
+    int_readers!(c_i16_le, o_i16_le, i16, 2, from_le_bytes);
+    int_readers!(c_i16_be, o_i16_be, i16, 2, from_be_bytes);
+    int_readers!(c_u32_le, o_u32_le, u32, 4, from_le_bytes);
+    int_readers!(c_u32_be, o_u32_be, u32, 4, from_be_bytes);
+    int_readers!(c_i32_le, o_i32_le, i32, 4, from_le_bytes);
+    int_readers!(c_i32_be, o_i32_be, i32, 4, from_be_bytes);
+
+    fn c_data(self, range: std::ops::Range<usize>) -> Result<&'d [u8]> {
+        self.get(range.clone())
+            .ok_or_else(|| anyhow!("not enough data at offset {}", range.start))
+    }
+
+    fn c_ident(self, i: usize) -> Result<[u8; 4]> {
+        self.get(i..i + 4)
+            .ok_or_else(|| anyhow!("not enough data at offset {}", i))
+            .map(|s| s.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+
+    fn o_data(self, range: std::ops::Range<usize>) -> Option<&'d [u8]> {
+        self.c_data(range).ok()
+    }
+
+    fn o_ident(self, i: usize) -> Option<[u8; 4]> {
+        self.c_ident(i).ok()
+    }
+}
+
+/// An integer discriminant read while parsing a `repr_enum!` that didn't match any of its mapped
+/// variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReprError<T> {
+    pub value: T,
+}
+
+impl<T: Display> Display for ReprError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unrecognized discriminant: {}", self.value)
+    }
+}
+
+impl<T: std::fmt::Debug + Display> std::error::Error for ReprError<T> {}
+
+/// Declares a fieldless enum mapping small integer discriminants to variants (e.g. a tagged field
+/// in a binary asset format), plus a `from_repr` that matches each mapping and returns
+/// `Err(ReprError { value })` for anything unrecognized, instead of a manual match scattered at
+/// every call site parsing that field.
+#[macro_export]
+macro_rules! repr_enum {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident: $repr:ty {
+        $($disc:literal => $variant:ident),+ $(,)?
+    }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        $vis enum $name {
+            $($variant,)+
+        }
+
+        impl $name {
+            pub fn from_repr(n: $repr) -> Result<Self, $crate::utils::ReprError<$repr>> {
+                match n {
+                    $($disc => Ok(Self::$variant),)+
+                    value => Err($crate::utils::ReprError { value }),
+                }
+            }
+        }
+    };
+}
+
+/// A space-efficient `Option<u16>` for a binary format's "no value" sentinel (`u16::MAX` meaning
+/// absent), the same size as a raw `u16` via `NonZeroU16`'s niche rather than a separate
+/// discriminant.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct OptU16(Option<NonZeroU16>);
+
+impl OptU16 {
+    pub fn from_repr(n: u16) -> Self {
+        if n == u16::MAX {
+            Self(None)
+        } else {
+            Self(NonZeroU16::new(n + 1))
+        }
+    }
+
+    pub fn get(self) -> Option<u16> {
+        self.0.map(|n| n.get() - 1)
+    }
+}
+
+impl fmt::Debug for OptU16 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(value) => write!(f, "{value}"),
+            None => write!(f, "None"),
+        }
+    }
+}
+
 pub fn print_textual_items<T>(
     desc: &'static str,
     items: &[T],
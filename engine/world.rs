@@ -15,7 +15,14 @@ pub struct World {
     sizes: Vec<u32>,
     /// A 3-dimensional array of `(top, bot): (u32, u32)`
     spans: Vec<u32>,
+    /// Per-column slot capacity `spans` was allocated with: `sy` rounded up to the next even
+    /// number, since a column alternating solid/empty one voxel at a time can produce up to
+    /// `(sy + 1) / 2` spans (2 slots each), one more than `sy / 2` when `sy` is odd.
+    span_slots: u32,
     needs_upload: bool,
+    /// `(x, z)` columns touched by `set_voxel` since the last `clear_dirty`, so the renderer can
+    /// upload just the changed regions of `sizes`/`spans` instead of the whole volume.
+    dirty_columns: Vec<(u32, u32)>,
 }
 
 struct Array3D<T: Copy> {
@@ -40,9 +47,31 @@ impl World {
         Self::from_array(&arr)
     }
 
+    /// Builds a world from fractal value-noise terrain instead of test spheres. `octaves` sums
+    /// that many doublings of `base_frequency` (each at half the previous octave's amplitude,
+    /// starting from `amplitude`) into a height field scaled to `[0, sy)`, deterministic for a
+    /// given `seed`.
+    pub fn new_terrain(
+        sx: usize,
+        sy: usize,
+        sz: usize,
+        seed: u64,
+        octaves: u32,
+        base_frequency: f32,
+        amplitude: f32,
+    ) -> Self {
+        let mut arr = Array3D::new(0, sx, sy, sz);
+
+        arr.fill_terrain(seed, octaves, base_frequency, amplitude);
+
+        Self::from_array(&arr)
+    }
+
     fn from_array(arr: &Array3D<u32>) -> Self {
+        let span_slots = arr.sy + arr.sy % 2;
+
         let mut sizes = Array2D::new(0, arr.sx, arr.sz);
-        let mut spans = Array3D::new(0, arr.sx, arr.sy, arr.sz);
+        let mut spans = Array3D::new(0, arr.sx, span_slots, arr.sz);
 
         for x in 0..arr.sx {
             for z in 0..arr.sz {
@@ -74,8 +103,10 @@ impl World {
             sy: to_u32(arr.sy),
             sz: to_u32(arr.sz),
             spans: spans.data,
+            span_slots: to_u32(span_slots),
             sizes: sizes.data,
             needs_upload: true,
+            dirty_columns: vec![],
         }
     }
 
@@ -106,6 +137,112 @@ impl World {
     pub fn uploaded(&mut self) {
         self.needs_upload = false;
     }
+
+    pub fn dirty_columns(&self) -> &[(u32, u32)] {
+        &self.dirty_columns
+    }
+
+    pub fn clear_dirty(&mut self) {
+        self.dirty_columns.clear();
+    }
+
+    /// Sets the voxel at `(x, y, z)` solid or empty, recomputing only the `(top, bot)` span list
+    /// of the affected `(x, z)` column instead of re-deriving the whole volume from a full 3D
+    /// array. Marks the column dirty so the renderer can upload just the changed region.
+    pub fn set_voxel(&mut self, x: u32, y: u32, z: u32, solid: bool) {
+        assert!(x < self.sx, "x out of bounds: {} >= {}", x, self.sx);
+        assert!(y < self.sy, "y out of bounds: {} >= {}", y, self.sy);
+        assert!(z < self.sz, "z out of bounds: {} >= {}", z, self.sz);
+
+        let mut spans = self.column_spans(x, z);
+        let covering = spans.iter().position(|&(bot, top)| y >= bot && y < top);
+
+        match (solid, covering) {
+            (true, None) => {
+                spans.push((y, y + 1));
+                spans.sort_unstable_by_key(|&(bot, _)| bot);
+                merge_adjacent_spans(&mut spans);
+            }
+            (false, Some(i)) => {
+                let (bot, top) = spans.remove(i);
+
+                if y > bot {
+                    spans.push((bot, y));
+                }
+                if y + 1 < top {
+                    spans.push((y + 1, top));
+                }
+
+                spans.sort_unstable_by_key(|&(bot, _)| bot);
+            }
+            // Already solid or already empty: nothing to change.
+            (true, Some(_)) | (false, None) => {}
+        }
+
+        self.write_column_spans(x, z, &spans);
+
+        if !self.dirty_columns.contains(&(x, z)) {
+            self.dirty_columns.push((x, z));
+        }
+
+        self.needs_upload = true;
+    }
+
+    fn sizes_index(&self, x: u32, z: u32) -> usize {
+        (z * self.sx + x) as usize
+    }
+
+    fn spans_index(&self, x: u32, slot: u32, z: u32) -> usize {
+        (z * self.span_slots * self.sx + slot * self.sx + x) as usize
+    }
+
+    fn column_spans(&self, x: u32, z: u32) -> Vec<(u32, u32)> {
+        let count = self.sizes[self.sizes_index(x, z)] as usize;
+
+        (0..count)
+            .map(|i| {
+                let slot = to_u32(i * 2);
+                let bot = self.spans[self.spans_index(x, slot, z)];
+                let top = self.spans[self.spans_index(x, slot + 1, z)];
+
+                (bot, top)
+            })
+            .collect()
+    }
+
+    fn write_column_spans(&mut self, x: u32, z: u32, spans: &[(u32, u32)]) {
+        assert!(
+            spans.len() * 2 <= self.span_slots as usize,
+            "too many spans in column ({x}, {z}) for world height {}",
+            self.sy
+        );
+
+        for (i, &(bot, top)) in spans.iter().enumerate() {
+            let slot = to_u32(i * 2);
+            let bot_idx = self.spans_index(x, slot, z);
+            let top_idx = self.spans_index(x, slot + 1, z);
+
+            self.spans[bot_idx] = bot;
+            self.spans[top_idx] = top;
+        }
+
+        self.sizes[self.sizes_index(x, z)] = to_u32(spans.len());
+    }
+}
+
+/// Merges spans that are now touching or overlapping after inserting a new unit span, assuming
+/// `spans` is sorted by `bot`.
+fn merge_adjacent_spans(spans: &mut Vec<(u32, u32)>) {
+    let mut merged: Vec<(u32, u32)> = Vec::with_capacity(spans.len());
+
+    for &(bot, top) in spans.iter() {
+        match merged.last_mut() {
+            Some((_, last_top)) if bot <= *last_top => *last_top = (*last_top).max(top),
+            _ => merged.push((bot, top)),
+        }
+    }
+
+    *spans = merged;
 }
 
 impl<T: Copy> Array3D<T> {
@@ -185,6 +322,95 @@ impl Array3D<u32> {
             }
         }
     }
+
+    /// Fills each `(x, z)` column solid up to a fractal value-noise height, scaled to
+    /// `[0, sy)`. See `fbm_height` for the octave summation.
+    fn fill_terrain(&mut self, seed: u64, octaves: u32, base_frequency: f32, amplitude: f32) {
+        for x in 0..self.sx {
+            for z in 0..self.sz {
+                let xf = to_f32(to_u32(x));
+                let zf = to_f32(to_u32(z));
+                let noise = fbm_height(seed, xf, zf, octaves, base_frequency, amplitude);
+
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let height = (noise * to_f32(to_u32(self.sy))) as usize;
+                let height = height.min(self.sy);
+
+                for y in 0..height {
+                    self.set(x, y, z, 1);
+                }
+            }
+        }
+    }
+}
+
+/// Sums `octaves` layers of `value_noise`, each doubling the previous octave's frequency and
+/// halving its amplitude (fractal Brownian motion), then normalizes the result to `[0, 1)` by
+/// dividing by the total amplitude summed across all octaves.
+fn fbm_height(seed: u64, x: f32, z: f32, octaves: u32, base_frequency: f32, amplitude: f32) -> f32 {
+    let mut frequency = base_frequency;
+    let mut amp = amplitude;
+    let mut total = 0.0;
+    let mut amp_sum = 0.0;
+
+    for octave in 0..octaves {
+        let octave_seed = seed.wrapping_add(u64::from(octave));
+
+        total += value_noise(octave_seed, x * frequency, z * frequency) * amp;
+        amp_sum += amp;
+
+        frequency *= 2.0;
+        amp *= 0.5;
+    }
+
+    if amp_sum > 0.0 {
+        total / amp_sum
+    } else {
+        0.0
+    }
+}
+
+/// Value noise at `(x, z)`: hashes the four integer lattice points surrounding `(x, z)` and
+/// bilinearly interpolates between them, using a smoothstep fade so the result has continuous
+/// derivatives across lattice boundaries instead of visible grid creases.
+fn value_noise(seed: u64, x: f32, z: f32) -> f32 {
+    let x0 = x.floor();
+    let z0 = z.floor();
+
+    #[allow(clippy::cast_possible_truncation)]
+    let xi = x0 as i64;
+    #[allow(clippy::cast_possible_truncation)]
+    let zi = z0 as i64;
+
+    let tx = smoothstep(x - x0);
+    let tz = smoothstep(z - z0);
+
+    let v00 = lattice_value(seed, xi, zi);
+    let v10 = lattice_value(seed, xi + 1, zi);
+    let v01 = lattice_value(seed, xi, zi + 1);
+    let v11 = lattice_value(seed, xi + 1, zi + 1);
+
+    lerp(lerp(v00, v10, tx), lerp(v01, v11, tx), tz)
+}
+
+/// Deterministically hashes an integer lattice point `(xi, zi)` to a pseudo-random float in
+/// `[0, 1)`, by mixing it into `seed` with wrapping arithmetic and running the result through
+/// `Wyhash64`.
+#[allow(clippy::cast_sign_loss)]
+fn lattice_value(seed: u64, xi: i64, zi: i64) -> f32 {
+    let mixed_seed = seed
+        .wrapping_add((xi as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+        .wrapping_add((zi as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f));
+
+    rand::Wyhash64::from_seed(mixed_seed).gen_f32()
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
 }
 
 impl<T: Copy> Array2D<T> {
@@ -0,0 +1,71 @@
+//! Writes an uncompressed YUV4MPEG2 (Y4M) stream: a single text header line, then one `FRAME\n`
+//! plus raw planar sample data per frame. Used by `MainLoop::benchmark`'s `--record` flag for
+//! frame-accurate visual diffing of benchmark runs, without pulling in a real video encoder.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{ensure, Result};
+
+/// An open Y4M file with its header already written, ready for `write_frame` calls. Always
+/// `C444` (no chroma subsampling), since the source is a full-resolution RGB framebuffer and
+/// subsampling would just throw detail away that a diff tool might want to see.
+pub struct Y4mWriter {
+    file: BufWriter<File>,
+    width: u32,
+    height: u32,
+}
+
+impl Y4mWriter {
+    pub fn new(path: &Path, width: u32, height: u32, fps_num: u32, fps_den: u32) -> Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+
+        writeln!(file, "YUV4MPEG2 W{width} H{height} F{fps_num}:{fps_den} Ip A1:1 C444")?;
+
+        Ok(Self { file, width, height })
+    }
+
+    /// Appends one frame. `rgb` is `width * height * 3` bytes, row-major, 8 bits per channel.
+    pub fn write_frame(&mut self, rgb: &[u8]) -> Result<()> {
+        let expected_len = self.width as usize * self.height as usize * 3;
+        ensure!(rgb.len() == expected_len, "expected {expected_len} RGB bytes, got {}", rgb.len());
+
+        let num_pixels = self.width as usize * self.height as usize;
+        let mut y_plane = Vec::with_capacity(num_pixels);
+        let mut u_plane = Vec::with_capacity(num_pixels);
+        let mut v_plane = Vec::with_capacity(num_pixels);
+
+        for pixel in rgb.chunks_exact(3) {
+            let (y, u, v) = rgb_to_yuv(pixel[0], pixel[1], pixel[2]);
+
+            y_plane.push(y);
+            u_plane.push(u);
+            v_plane.push(v);
+        }
+
+        self.file.write_all(b"FRAME\n")?;
+        self.file.write_all(&y_plane)?;
+        self.file.write_all(&u_plane)?;
+        self.file.write_all(&v_plane)?;
+
+        Ok(())
+    }
+}
+
+/// `Y=0.299R+0.587G+0.114B`, `U=128-0.169R-0.331G+0.5B`, `V=128+0.5R-0.419G-0.081B`, each clamped
+/// to `0..=255`.
+fn rgb_to_yuv(r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+    let (r, g, b) = (f32::from(r), f32::from(g), f32::from(b));
+
+    let y = 0.299 * r + 0.587 * g + 0.114 * b;
+    let u = 128.0 - 0.169 * r - 0.331 * g + 0.5 * b;
+    let v = 128.0 + 0.5 * r - 0.419 * g - 0.081 * b;
+
+    (clamp_to_u8(y), clamp_to_u8(u), clamp_to_u8(v))
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn clamp_to_u8(x: f32) -> u8 {
+    x.round().clamp(0.0, 255.0) as u8
+}
@@ -1,5 +1,6 @@
 #![allow(clippy::uninlined_format_args)]
 
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use anyhow::Result;
@@ -17,7 +18,7 @@ fn main() -> Result<()> {
     let mut main_loop = MainLoop::new(res, "game")?;
 
     if let Some(frames) = args.benchmark {
-        main_loop.benchmark(frames);
+        main_loop.benchmark(frames, args.record.as_deref())?;
         return Ok(());
     }
 
@@ -30,6 +31,7 @@ struct Args {
     log_level: LevelFilter,
     verbose: bool,
     benchmark: Option<usize>,
+    record: Option<PathBuf>,
 }
 
 fn parse_args() -> Args {
@@ -37,6 +39,7 @@ fn parse_args() -> Args {
         log_level: LevelFilter::Info,
         verbose: false,
         benchmark: None,
+        record: None,
     };
 
     let passed_args = std::env::args().collect::<Vec<String>>();
@@ -63,6 +66,10 @@ fn parse_args() -> Args {
                 args.benchmark = Some(frames);
                 it = rest;
             }
+            ["-r" | "--record", path, rest @ ..] => {
+                args.record = Some(PathBuf::from(path));
+                it = rest;
+            }
             ["-v" | "--verbose", rest @ ..] => {
                 args.verbose = true;
                 it = rest;